@@ -0,0 +1,192 @@
+use std::{
+    iter::Sum,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+use rand_chacha::rand_core::RngCore;
+use rayon::prelude::*;
+
+use crate::{
+    cache::FitnessCache,
+    chromosome::Chromosome,
+    config::AnnealingConfig,
+    data::DataSet,
+    ga::{
+        evaluate_fitness_cached, finish_selector, new_rng, GaResult, GaRng, HallOfFame, Individual,
+        StopReason,
+    },
+    model::ModelName,
+    observer::GenerationStats,
+};
+
+// Random moves used to calibrate the initial temperature of a chain.
+const CALIBRATION_MOVES: usize = 10;
+
+// What one chain leaves behind for the combined result.
+struct Chain {
+    hall_of_fame: HallOfFame,
+    // current state and elapsed time after every step
+    trajectory: Vec<(Individual, Duration)>,
+}
+
+struct Annealer<'a, D> {
+    dataset: &'a D,
+    model: ModelName,
+    config: &'a AnnealingConfig,
+    cache: &'a FitnessCache,
+    num_features: usize,
+    started: Instant,
+}
+
+impl<D> Annealer<'_, D>
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    fn evaluate(&self, features: Chromosome) -> Individual {
+        let mut individual = Individual::from_features(features);
+        let negative_mse =
+            evaluate_fitness_cached(&individual, self.dataset, self.model.clone(), self.cache, 0);
+        individual.set_score(negative_mse, self.config.search.parsimony);
+        individual
+    }
+
+    // Flip 1..=max_flips random genes of `current`, then repair.
+    fn neighbour(&self, current: &Individual, rng: &mut GaRng) -> Chromosome {
+        let mut mask = current.features().clone();
+        let flips = rng.gen_range(1..=self.config.max_flips.clamp(1, self.num_features));
+        for _ in 0..flips {
+            mask.flip(rng.gen_range(0..self.num_features));
+        }
+        let mut neighbour = Individual::from_features(mask);
+        neighbour.repair(&self.config.search.constraints, rng);
+        neighbour.features().clone()
+    }
+
+    fn run_chain(&self, index: usize, seed: u64, calibration: usize, steps: usize) -> Chain {
+        let mut rng = new_rng(Some(seed));
+        rng.set_stream(index as u64);
+        let mut hall_of_fame = HallOfFame::new(self.config.search.hall_of_fame_size.max(1));
+
+        let mut start = Individual::new(self.num_features, &mut rng);
+        start.repair(&self.config.search.constraints, &mut rng);
+        let mut current = self.evaluate(start.features().clone());
+        hall_of_fame.update(std::slice::from_ref(&current));
+
+        let initial_temperature = match self.config.initial_temperature {
+            Some(temperature) => temperature,
+            None => {
+                let moves: Vec<Individual> = (0..calibration)
+                    .map(|_| self.evaluate(self.neighbour(&current, &mut rng)))
+                    .collect();
+                hall_of_fame.update(&moves);
+                let deltas: Vec<f64> = moves
+                    .iter()
+                    .map(|candidate| (candidate.fitness() - current.fitness()).abs() as f64)
+                    .filter(|delta| delta.is_finite())
+                    .collect();
+                let mean = deltas.iter().sum::<f64>() / deltas.len().max(1) as f64;
+                (mean / std::f64::consts::LN_2).max(f64::EPSILON)
+            }
+        };
+
+        let mut trajectory = Vec::with_capacity(steps);
+        for step in 0..steps {
+            let temperature = self
+                .config
+                .cooling
+                .temperature(initial_temperature, step, steps);
+            let candidate = self.evaluate(self.neighbour(&current, &mut rng));
+            hall_of_fame.update(std::slice::from_ref(&candidate));
+            let delta = (candidate.fitness() - current.fitness()) as f64;
+            let accept = delta >= 0.0
+                || (temperature > 0.0 && rng.gen::<f64>() < (delta / temperature).exp());
+            if accept {
+                current = candidate;
+            }
+            trajectory.push((current.clone(), self.started.elapsed()));
+        }
+        Chain {
+            hall_of_fame,
+            trajectory,
+        }
+    }
+}
+
+// Simulated annealing over feature masks: `config.chains` independent chains run in parallel,
+// each starting from a random mask and moving by flipping a few genes. Worse moves are accepted
+// with probability exp(delta / T) under the cooling schedule. All chains share one fitness
+// cache and together spend `config.search.evaluations` evaluations, so the result compares with a GA
+// run of the same budget. Step k of the history holds the current state of every chain.
+pub fn run_annealing<D>(
+    dataset: Arc<D>,
+    model: ModelName,
+    config: AnnealingConfig,
+) -> Result<GaResult, String>
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    let num_features = dataset.dimension().1;
+    config.search.constraints.validate(num_features)?;
+    let chains = config.chains.max(1);
+    // every chain spends one evaluation on its start and some on calibration, the rest are steps
+    let per_chain = (config.search.evaluations / chains).max(1);
+    let calibration = if config.initial_temperature.is_some() {
+        0
+    } else {
+        CALIBRATION_MOVES.min(per_chain / 2)
+    };
+    let steps = per_chain - 1 - calibration;
+    let seed = new_rng(config.search.seed).next_u64();
+
+    let cache = FitnessCache::new();
+    let annealer = Annealer {
+        dataset: dataset.as_ref(),
+        model,
+        config: &config,
+        cache: &cache,
+        num_features,
+        started: Instant::now(),
+    };
+    let results: Vec<Chain> = (0..chains)
+        .into_par_iter()
+        .map(|index| annealer.run_chain(index, seed, calibration, steps))
+        .collect();
+
+    let mut hall_of_fame = HallOfFame::new(config.search.hall_of_fame_size.max(1));
+    for chain in &results {
+        hall_of_fame.update(chain.hall_of_fame.members());
+    }
+    let setup = chains * (per_chain - steps);
+    let history: Vec<GenerationStats> = (0..steps)
+        .map(|step| {
+            let states: Vec<Individual> = results
+                .iter()
+                .map(|chain| chain.trajectory[step].0.clone())
+                .collect();
+            let elapsed = results
+                .iter()
+                .map(|chain| chain.trajectory[step].1)
+                .max()
+                .unwrap_or_default();
+            GenerationStats::from_population(
+                0,
+                step + 1,
+                &states,
+                elapsed,
+                setup + (step + 1) * chains,
+            )
+        })
+        .collect();
+
+    Ok(finish_selector(
+        hall_of_fame,
+        history,
+        StopReason::BudgetExhausted,
+        &cache,
+        num_features,
+    ))
+}
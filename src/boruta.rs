@@ -0,0 +1,213 @@
+use rand::{seq::SliceRandom, RngCore};
+use smartcore::{
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    math::num::RealNumber,
+};
+
+use crate::{
+    chromosome::Chromosome,
+    config::BorutaConfig,
+    data::DataSet,
+    ga::{new_rng, GaRng},
+    importance::permutation_importances_matrix,
+    model::ModelName,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BorutaDecision {
+    // significantly more important than the best shadow feature
+    Confirmed,
+    // no decision within `max_iterations`
+    Tentative,
+    // significantly less important than the best shadow feature; no longer fitted
+    Rejected,
+}
+
+#[derive(Clone, Debug)]
+pub struct BorutaResult {
+    // one per feature, in column order
+    pub decisions: Vec<(String, BorutaDecision)>,
+    // iterations in which the feature beat the best shadow
+    pub hits: Vec<usize>,
+    // iterations the feature took part in
+    pub trials: Vec<usize>,
+    pub iterations: usize,
+}
+
+impl BorutaResult {
+    pub fn confirmed(&self) -> Chromosome {
+        self.mask(|decision| decision == BorutaDecision::Confirmed)
+    }
+
+    // number of features that ended with `decision`
+    pub fn count(&self, decision: BorutaDecision) -> usize {
+        self.decisions
+            .iter()
+            .filter(|(_, other)| *other == decision)
+            .count()
+    }
+
+    // Confirmed and tentative features, for callers that rather keep too many than too few.
+    pub fn confirmed_or_tentative(&self) -> Chromosome {
+        self.mask(|decision| decision != BorutaDecision::Rejected)
+    }
+
+    fn mask(&self, keep: impl Fn(BorutaDecision) -> bool) -> Chromosome {
+        Chromosome::from(
+            self.decisions
+                .iter()
+                .map(|(_, decision)| keep(*decision))
+                .collect::<Vec<bool>>(),
+        )
+    }
+}
+
+// P(X >= hits) and P(X <= hits) for X ~ Binomial(trials, 0.5).
+fn binomial_tails(hits: usize, trials: usize) -> (f64, f64) {
+    let ln_half = 0.5f64.ln();
+    let mut ln_choose = 0.0;
+    let (mut upper, mut lower) = (0.0, 0.0);
+    for i in 0..=trials {
+        if i > 0 {
+            ln_choose += ((trials - i + 1) as f64).ln() - (i as f64).ln();
+        }
+        let probability = (ln_choose + trials as f64 * ln_half).exp();
+        if i >= hits {
+            upper += probability;
+        }
+        if i <= hits {
+            lower += probability;
+        }
+    }
+    (upper.min(1.0), lower.min(1.0))
+}
+
+// `x` with a shuffled copy of every column appended on the right.
+fn with_shadows<T: RealNumber>(x: &DenseMatrix<T>, rng: &mut GaRng) -> DenseMatrix<T> {
+    let (rows, cols) = x.shape();
+    let orders: Vec<Vec<usize>> = (0..cols)
+        .map(|_| {
+            let mut order: Vec<usize> = (0..rows).collect();
+            order.shuffle(rng);
+            order
+        })
+        .collect();
+    let mut values = Vec::with_capacity(rows * cols * 2);
+    for row in 0..rows {
+        values.extend((0..cols).map(|col| x.get(row, col)));
+        values.extend(
+            orders
+                .iter()
+                .enumerate()
+                .map(|(col, order)| x.get(order[row], col)),
+        );
+    }
+    DenseMatrix::from_vec(rows, cols * 2, &values)
+}
+
+// Boruta all-relevant selection. Every iteration appends a shuffled "shadow" copy of each
+// feature still in play, fits the random forest of `ModelName::DecisionTreeRegressor` and
+// compares permutation importances: a feature scores a hit when it beats the best shadow. A
+// two-sided binomial test on the hits, Bonferroni-corrected, confirms or rejects features;
+// rejected ones are dropped from later fits. Unlike the wrapper searches this keeps every
+// feature that carries signal, redundant or not.
+pub fn run_boruta<D>(dataset: &D, config: &BorutaConfig) -> BorutaResult
+where
+    D: DataSet,
+    D::Input: Send + Sync,
+{
+    let names = dataset.features();
+    let num_features = dataset.dimension().1;
+    let y = dataset.target();
+    let mut rng = new_rng(config.seed);
+    let mut decisions = vec![BorutaDecision::Tentative; num_features];
+    let mut decided = vec![false; num_features];
+    let mut hits = vec![0usize; num_features];
+    let mut trials = vec![0usize; num_features];
+    let mut iterations = 0;
+
+    while iterations < config.max_iterations && decided.iter().any(|done| !done) {
+        let active = Chromosome::from(
+            decisions
+                .iter()
+                .map(|&decision| decision != BorutaDecision::Rejected)
+                .collect::<Vec<bool>>(),
+        );
+        let Some(x) = dataset.select_columns(&active) else {
+            break;
+        };
+        let features: Vec<usize> = active.iter_ones().collect();
+        let x = with_shadows(&x, &mut rng);
+        let importances = permutation_importances_matrix(
+            &x,
+            &y,
+            &ModelName::DecisionTreeRegressor,
+            Some(rng.next_u64()),
+        );
+        let (real, shadow) = importances.split_at(features.len());
+        let shadow_max = shadow.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        iterations += 1;
+        for (&feature, &importance) in features.iter().zip(real) {
+            trials[feature] += 1;
+            if importance > shadow_max {
+                hits[feature] += 1;
+            }
+        }
+
+        let undecided: Vec<usize> = (0..num_features).filter(|&f| !decided[f]).collect();
+        let threshold = config.p_value / undecided.len().max(1) as f64;
+        for feature in undecided {
+            let (upper, lower) = binomial_tails(hits[feature], trials[feature]);
+            if upper < threshold {
+                decisions[feature] = BorutaDecision::Confirmed;
+                decided[feature] = true;
+            } else if lower < threshold {
+                decisions[feature] = BorutaDecision::Rejected;
+                decided[feature] = true;
+            }
+        }
+    }
+
+    BorutaResult {
+        decisions: names.into_iter().zip(decisions).collect(),
+        hits,
+        trials,
+        iterations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // P(X = hits) for X ~ Binomial(trials, 1/2)
+    fn probability(hits: usize, trials: usize) -> f64 {
+        let choose = (0..hits).fold(1.0, |acc, i| acc * (trials - i) as f64 / (i + 1) as f64);
+        choose * 0.5f64.powi(trials as i32)
+    }
+
+    #[test]
+    fn tails_overlap_in_one_outcome() {
+        for trials in [1, 10, 25] {
+            for hits in 0..=trials {
+                let (upper, lower) = binomial_tails(hits, trials);
+                let total = 1.0 + probability(hits, trials);
+                assert!(
+                    (upper + lower - total).abs() < 1e-9,
+                    "{} of {}",
+                    hits,
+                    trials
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tails_are_symmetric() {
+        let (upper, lower) = binomial_tails(7, 20);
+        let (mirrored_upper, mirrored_lower) = binomial_tails(13, 20);
+        assert!((upper - mirrored_lower).abs() < 1e-12);
+        assert!((lower - mirrored_upper).abs() < 1e-12);
+        assert!((binomial_tails(0, 20).0 - 1.0).abs() < 1e-9);
+    }
+}
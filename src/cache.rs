@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+use crate::chromosome::Chromosome;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+};
+
+// Identifies one fitness evaluation: the selected feature mask on a given cross-validation fold.
+// Runs that are not part of a cross-validation use fold 0.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CacheKey {
+    pub fold: usize,
+    pub features: Chromosome,
+}
+
+// Fitness values already computed during a run, shared by all rayon workers.
+#[derive(Debug, Default)]
+pub struct FitnessCache {
+    entries: RwLock<HashMap<CacheKey, f32>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+// Plain copy of a cache's contents and counters, e.g. for checkpoints.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CacheSnapshot {
+    pub entries: Vec<(CacheKey, f32)>,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub entries: usize,
+}
+
+impl CacheStats {
+    pub fn lookups(&self) -> usize {
+        self.hits + self.misses
+    }
+
+    pub fn hit_rate(&self) -> f32 {
+        if self.lookups() == 0 {
+            return 0.0;
+        }
+        self.hits as f32 / self.lookups() as f32
+    }
+}
+
+impl FitnessCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Return the cached fitness for `features` on `fold`, or compute it with `evaluate` and
+    // remember the result. Two workers racing on the same unseen key may both evaluate it;
+    // that only costs time, since the fitness of a mask on a fold is deterministic.
+    pub fn get_or_insert_with<F>(&self, fold: usize, features: &Chromosome, evaluate: F) -> f32
+    where
+        F: FnOnce() -> f32,
+    {
+        let key = CacheKey {
+            fold,
+            features: features.clone(),
+        };
+        if let Some(&fitness) = self.entries.read().unwrap().get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return fitness;
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let fitness = evaluate();
+        self.entries.write().unwrap().insert(key, fitness);
+        fitness
+    }
+
+    pub fn from_snapshot(snapshot: CacheSnapshot) -> Self {
+        Self {
+            entries: RwLock::new(snapshot.entries.into_iter().collect()),
+            hits: AtomicUsize::new(snapshot.hits),
+            misses: AtomicUsize::new(snapshot.misses),
+        }
+    }
+
+    // Add the entries of `snapshot` that are missing here. Snapshots of one shared cache only
+    // grow, so the larger counters are the more recent ones.
+    pub fn merge(&self, snapshot: CacheSnapshot) {
+        let mut entries = self.entries.write().unwrap();
+        for (key, fitness) in snapshot.entries {
+            entries.entry(key).or_insert(fitness);
+        }
+        self.hits.fetch_max(snapshot.hits, Ordering::Relaxed);
+        self.misses.fetch_max(snapshot.misses, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> CacheSnapshot {
+        CacheSnapshot {
+            entries: self
+                .entries
+                .read()
+                .unwrap()
+                .iter()
+                .map(|(key, &fitness)| (key.clone(), fitness))
+                .collect(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.read().unwrap().len(),
+        }
+    }
+}
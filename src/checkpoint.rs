@@ -0,0 +1,116 @@
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cache::{CacheSnapshot, CacheStats},
+    data::DataSet,
+    filter,
+    ga::{GaResult, HallOfFame, Individual, Island, StopReason},
+    model::ModelName,
+    observer::GenerationStats,
+};
+
+// Full state of a GA run between two generations, enough to continue it exactly where it
+// stopped: the islands with their populations and RNG streams, the hall of fame, the history so
+// far, the fitness cache and the counters of the stopping criteria.
+#[derive(Serialize, Deserialize)]
+pub struct GaCheckpoint {
+    // what the cached fitness values were computed on; a run only resumes from a match
+    pub(crate) model: ModelName,
+    pub(crate) num_samples: usize,
+    pub(crate) num_features: usize,
+    pub(crate) data_fingerprint: u64,
+    // folds of the cross-validation the run belongs to; `None` for `run_ga`
+    pub(crate) k_folds: Option<usize>,
+    pub(crate) generations_run: usize,
+    pub(crate) islands: Vec<Island>,
+    pub(crate) hall_of_fame: HallOfFame,
+    pub(crate) history: Vec<GenerationStats>,
+    pub(crate) cache: CacheSnapshot,
+    pub(crate) best_fitness: f32,
+    pub(crate) generations_without_improvement: usize,
+    pub(crate) elapsed: Duration,
+    // set by the checkpoint written when the run ends
+    pub(crate) stop_reason: Option<StopReason>,
+}
+
+// Checkpoint file of one fold of a cross-validation run: `path` with `.fold<k>` appended.
+pub fn fold_checkpoint_path(path: &Path, fold: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".fold{}", fold));
+    PathBuf::from(name)
+}
+
+// FNV-1a hash of every feature value and target of `dataset`. Unlike `DefaultHasher` it is the
+// same in every build, so checkpoints stay comparable across versions.
+pub(crate) fn data_fingerprint<D: DataSet>(dataset: &D) -> u64 {
+    let values = filter::columns(dataset)
+        .into_iter()
+        .flatten()
+        .chain(filter::target(dataset));
+    values.fold(0xcbf2_9ce4_8422_2325, |hash, value| {
+        value
+            .to_bits()
+            .to_le_bytes()
+            .iter()
+            .fold(hash, |hash, &byte| {
+                (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+            })
+    })
+}
+
+impl GaCheckpoint {
+    pub fn generations_run(&self) -> usize {
+        self.generations_run
+    }
+
+    pub fn hall_of_fame(&self) -> &HallOfFame {
+        &self.hall_of_fame
+    }
+
+    // Whether the run had already ended when this checkpoint was written.
+    pub fn is_finished(&self) -> bool {
+        self.stop_reason.is_some()
+    }
+
+    // The result of a finished run, without running anything again.
+    pub(crate) fn into_result(self, cache_stats: CacheStats) -> Option<GaResult> {
+        let stop_reason = self.stop_reason?;
+        let best = self
+            .hall_of_fame
+            .best()
+            .cloned()
+            .unwrap_or_else(|| Individual::new_all_true(self.num_features));
+        Some(GaResult {
+            best,
+            hall_of_fame: self.hall_of_fame,
+            stop_reason,
+            generations_run: self.generations_run,
+            history: self.history,
+            cache_stats,
+        })
+    }
+
+    // Write to a temporary file first so a crash while saving leaves the previous
+    // checkpoint intact.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        // appended rather than replacing the extension, so fold files do not share one
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        let file = File::create(&tmp_path).map_err(|e| e.to_string())?;
+        bincode::serialize_into(BufWriter::new(file), self).map_err(|e| e.to_string())?;
+        fs::rename(&tmp_path, path).map_err(|e| e.to_string())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| e.to_string())?;
+        bincode::deserialize_from(BufReader::new(file)).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,229 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+const BLOCK_BITS: usize = u64::BITS as usize;
+
+// Bit-packed feature mask: bit `i` is set when feature `i` is selected. Bits past `len` in the
+// last block are always kept at zero, so equality, hashing and popcount can work on whole blocks.
+#[derive(Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Chromosome {
+    blocks: Vec<u64>,
+    len: usize,
+}
+
+impl Chromosome {
+    // no feature selected
+    pub fn new(len: usize) -> Self {
+        Self {
+            blocks: vec![0; len.div_ceil(BLOCK_BITS)],
+            len,
+        }
+    }
+
+    // every feature selected
+    pub fn new_all_true(len: usize) -> Self {
+        let mut chromosome = Self {
+            blocks: vec![u64::MAX; len.div_ceil(BLOCK_BITS)],
+            len,
+        };
+        chromosome.clear_tail();
+        chromosome
+    }
+
+    // Build a chromosome straight from packed blocks, e.g. random words; surplus bits are dropped.
+    pub fn from_blocks(len: usize, mut blocks: Vec<u64>) -> Self {
+        blocks.resize(len.div_ceil(BLOCK_BITS), 0);
+        let mut chromosome = Self { blocks, len };
+        chromosome.clear_tail();
+        chromosome
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn blocks(&self) -> &[u64] {
+        &self.blocks
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len, "gene {} out of range {}", index, self.len);
+        self.blocks[index / BLOCK_BITS] & (1 << (index % BLOCK_BITS)) != 0
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(index < self.len, "gene {} out of range {}", index, self.len);
+        let bit = 1 << (index % BLOCK_BITS);
+        if value {
+            self.blocks[index / BLOCK_BITS] |= bit;
+        } else {
+            self.blocks[index / BLOCK_BITS] &= !bit;
+        }
+    }
+
+    pub fn flip(&mut self, index: usize) {
+        assert!(index < self.len, "gene {} out of range {}", index, self.len);
+        self.blocks[index / BLOCK_BITS] ^= 1 << (index % BLOCK_BITS);
+    }
+
+    // number of selected features
+    pub fn count_ones(&self) -> usize {
+        self.blocks
+            .iter()
+            .map(|block| block.count_ones() as usize)
+            .sum()
+    }
+
+    // number of genes on which the two masks disagree
+    pub fn hamming_distance(&self, other: &Chromosome) -> usize {
+        assert_eq!(self.len, other.len, "chromosomes differ in length");
+        self.blocks
+            .iter()
+            .zip(other.blocks.iter())
+            .map(|(a, b)| (a ^ b).count_ones() as usize)
+            .sum()
+    }
+
+    // indices of the selected features, in increasing order
+    pub fn iter_ones(&self) -> IterOnes<'_> {
+        IterOnes {
+            blocks: &self.blocks,
+            block_index: 0,
+            current: self.blocks.first().copied().unwrap_or(0),
+        }
+    }
+
+    // every gene in order, as a `bool`
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(move |index| self.get(index))
+    }
+
+    pub fn to_bools(&self) -> Vec<bool> {
+        self.iter().collect()
+    }
+
+    fn clear_tail(&mut self) {
+        let used = self.len % BLOCK_BITS;
+        if used != 0 {
+            if let Some(last) = self.blocks.last_mut() {
+                *last &= (1 << used) - 1;
+            }
+        }
+    }
+}
+
+impl From<&[bool]> for Chromosome {
+    fn from(genes: &[bool]) -> Self {
+        let mut chromosome = Chromosome::new(genes.len());
+        for (index, _) in genes.iter().enumerate().filter(|(_, &gene)| gene) {
+            chromosome.set(index, true);
+        }
+        chromosome
+    }
+}
+
+impl From<Vec<bool>> for Chromosome {
+    fn from(genes: Vec<bool>) -> Self {
+        Chromosome::from(genes.as_slice())
+    }
+}
+
+impl fmt::Debug for Chromosome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+pub struct IterOnes<'a> {
+    blocks: &'a [u64],
+    block_index: usize,
+    current: u64,
+}
+
+impl Iterator for IterOnes<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.block_index += 1;
+            self.current = *self.blocks.get(self.block_index)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        // clear the lowest set bit
+        self.current &= self.current - 1;
+        Some(self.block_index * BLOCK_BITS + bit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_true_keeps_the_tail_clear() {
+        for len in [1, 63, 64, 65, 127, 128, 130] {
+            let chromosome = Chromosome::new_all_true(len);
+            assert_eq!(chromosome.count_ones(), len);
+            assert_eq!(chromosome.blocks().len(), len.div_ceil(BLOCK_BITS));
+            assert_eq!(chromosome.iter_ones().count(), len);
+        }
+    }
+
+    #[test]
+    fn from_blocks_drops_surplus_bits() {
+        let chromosome = Chromosome::from_blocks(65, vec![u64::MAX; 3]);
+        assert_eq!(chromosome.blocks(), &[u64::MAX, 1]);
+        assert_eq!(chromosome, Chromosome::new_all_true(65));
+
+        let short = Chromosome::from_blocks(130, vec![u64::MAX]);
+        assert_eq!(short.blocks(), &[u64::MAX, 0, 0]);
+        assert_eq!(short.count_ones(), 64);
+    }
+
+    #[test]
+    fn genes_on_block_boundaries() {
+        let mut chromosome = Chromosome::new(130);
+        for gene in [0, 63, 64, 127, 128, 129] {
+            chromosome.set(gene, true);
+            assert!(chromosome.get(gene));
+        }
+        assert_eq!(chromosome.count_ones(), 6);
+        chromosome.flip(64);
+        assert!(!chromosome.get(64));
+        chromosome.set(129, false);
+        assert_eq!(
+            chromosome.iter_ones().collect::<Vec<_>>(),
+            vec![0, 63, 127, 128]
+        );
+    }
+
+    #[test]
+    fn iter_ones_skips_empty_blocks() {
+        let mut chromosome = Chromosome::new(200);
+        for gene in [5, 130, 199] {
+            chromosome.set(gene, true);
+        }
+        assert_eq!(
+            chromosome.iter_ones().collect::<Vec<_>>(),
+            vec![5, 130, 199]
+        );
+        assert_eq!(Chromosome::new(130).iter_ones().next(), None);
+        assert_eq!(Chromosome::new(0).iter_ones().next(), None);
+    }
+
+    #[test]
+    fn bools_round_trip() {
+        let genes: Vec<bool> = (0..65).map(|gene| gene % 3 == 0 || gene == 64).collect();
+        let chromosome = Chromosome::from(genes.clone());
+        assert_eq!(chromosome.to_bools(), genes);
+        assert_eq!(
+            chromosome.hamming_distance(&Chromosome::new(65)),
+            chromosome.count_ones()
+        );
+    }
+}
@@ -1,7 +1,139 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use crate::{chromosome::Chromosome, constraints::FeatureConstraints, observer::GaObserver};
+
+// Penalty subtracted from the GA fitness (negative MSE) to prefer smaller feature sets. The
+// coefficient is in MSE units per selected feature.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Parsimony {
+    #[default]
+    None,
+    // coefficient * number of selected features
+    Linear(f32),
+    // coefficient * ln(1 + number of selected features)
+    Logarithmic(f32),
+}
+
+impl Parsimony {
+    pub fn penalty(&self, num_selected: usize) -> f32 {
+        match *self {
+            Parsimony::None => 0.0,
+            Parsimony::Linear(coefficient) => coefficient * num_selected as f32,
+            Parsimony::Logarithmic(coefficient) => coefficient * (num_selected as f32).ln_1p(),
+        }
+    }
+}
+
+// Which islands receive the migrants of an island.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MigrationTopology {
+    // island i sends to island i + 1, the last one to the first
+    #[default]
+    Ring,
+    // every island sends to every other island
+    FullyConnected,
+}
+
+// How generation 0 is drawn.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Initialization {
+    // every feature selected with probability 0.5
+    #[default]
+    Uniform,
+    // every feature selected with the given probability
+    Density(f64),
+    // the given fractions of the population start with every feature or with none; the rest
+    // is drawn uniformly
+    Mixed {
+        all_true: f64,
+        all_false: f64,
+    },
+    // features ordered best first, e.g. by `filter::correlation_ranking`; selection probability
+    // falls linearly from the top of the ranking to the bottom so the expected share of
+    // selected features is `density`
+    Ranked {
+        ranking: Vec<usize>,
+        density: f64,
+    },
+}
+
+// Keeps distinct good feature subsets alive instead of letting the population collapse onto
+// one mask.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Niching {
+    #[default]
+    None,
+    // parents are chosen by fitness shared with every individual closer than `radius`
+    // (normalised Hamming distance in [0, 1]); `alpha` shapes the sharing function, 1.0 is linear
+    FitnessSharing {
+        radius: f32,
+        alpha: f32,
+    },
+}
+
+// What happens to an individual whose mask already occurs earlier in the new generation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DuplicateHandling {
+    // duplicates stay and are scored again (from the fitness cache)
+    #[default]
+    Keep,
+    // replaced by a fresh random individual
+    ReplaceWithRandom,
+    // mutated again with this per-gene flip probability until the mask is new
+    ExtraMutation {
+        rate: f64,
+    },
+}
+
+// Distinct best feature sets every search remembers unless configured otherwise.
+pub const DEFAULT_HALL_OF_FAME_SIZE: usize = 10;
+
 #[derive(Clone)]
 pub struct GaConfig {
+    // population size of every island
     pub populaton_size: usize,
     pub generations: i32,
+    // number of top individuals copied unchanged into the next generation
+    pub elitism_count: usize,
+    // number of distinct best feature sets remembered across the run
+    pub hall_of_fame_size: usize,
+    // probability that a child is produced by uniform crossover of two parents instead of
+    // cloning one; 0.0 keeps the mutation-only reproduction
+    pub crossover_rate: f64,
+    // seed for every random GA step; `None` draws a fresh seed from the OS
+    pub seed: Option<u64>,
+    // stop once the best fitness has not improved for this many generations
+    pub stall_generations: Option<usize>,
+    // stop once the best fitness reaches this value (negative MSE minus any parsimony penalty)
+    pub target_fitness: Option<f32>,
+    // stop once population diversity (mean pairwise Hamming distance over the number of
+    // features, in [0, 1]) drops below this value
+    pub min_diversity: Option<f32>,
+    // stop once the run has taken longer than this
+    pub time_budget: Option<Duration>,
+    // size penalty applied on top of the raw error
+    pub parsimony: Parsimony,
+    // number of sub-populations evolving side by side; 1 runs a single population
+    pub islands: usize,
+    // migrate every this many generations; 0 disables migration
+    pub migration_interval: usize,
+    // number of best individuals each island sends per migration
+    pub migrant_count: usize,
+    pub migration_topology: MigrationTopology,
+    // required/excluded features and size bounds every evaluated mask satisfies
+    pub constraints: FeatureConstraints,
+    pub initialization: Initialization,
+    // known-good masks placed into generation 0 as they are (after repair), ahead of the drawn
+    // individuals
+    pub initial_masks: Vec<Chromosome>,
+    // file the full GA state is written to, see `ga::resume_ga`; `None` disables checkpoints
+    pub checkpoint_path: Option<PathBuf>,
+    // write a checkpoint every this many generations
+    pub checkpoint_interval: usize,
+    // notified as the run progresses, see `observer::GaObserver`
+    pub observers: Vec<Arc<dyn GaObserver>>,
+    pub niching: Niching,
+    pub duplicates: DuplicateHandling,
 }
 
 impl Default for GaConfig {
@@ -9,6 +141,382 @@ impl Default for GaConfig {
         Self {
             populaton_size: 50,
             generations: 50,
+            elitism_count: 2,
+            hall_of_fame_size: DEFAULT_HALL_OF_FAME_SIZE,
+            crossover_rate: 0.0,
+            seed: None,
+            stall_generations: None,
+            target_fitness: None,
+            min_diversity: None,
+            time_budget: None,
+            parsimony: Parsimony::None,
+            islands: 1,
+            migration_interval: 10,
+            migrant_count: 2,
+            migration_topology: MigrationTopology::Ring,
+            constraints: FeatureConstraints::default(),
+            initialization: Initialization::Uniform,
+            initial_masks: vec![],
+            checkpoint_path: None,
+            checkpoint_interval: 10,
+            observers: vec![],
+            niching: Niching::None,
+            duplicates: DuplicateHandling::Keep,
+        }
+    }
+}
+
+impl GaConfig {
+    // Check the parts of the config that depend on the dataset: the feature constraints and the
+    // length of every initial mask. Every GA entry point calls this before running.
+    pub fn validate(&self, num_features: usize) -> Result<(), String> {
+        self.constraints.validate(num_features)?;
+        if let Some(mask) = self
+            .initial_masks
+            .iter()
+            .find(|mask| mask.len() != num_features)
+        {
+            return Err(format!(
+                "initial mask has {} genes but the dataset has {} features",
+                mask.len(),
+                num_features
+            ));
+        }
+        Ok(())
+    }
+
+    // Defaults for `nsga::run_nsga2`. Without crossover most children are copies of a parent,
+    // which NSGA-II cannot use to spread the front.
+    pub fn nsga2() -> Self {
+        Self {
+            crossover_rate: 0.9,
+            ..Default::default()
+        }
+    }
+}
+
+// Direction of a sequential wrapper search.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SequentialDirection {
+    // start empty and add one feature per step (SFS)
+    #[default]
+    Forward,
+    // start with every feature and remove one per step (SBS)
+    Backward,
+}
+
+#[derive(Clone, Debug)]
+pub struct SequentialConfig {
+    pub direction: SequentialDirection,
+    // after every step, undo earlier steps while that improves on the best subset of the same
+    // size (SFFS / SBFS)
+    pub floating: bool,
+    // required/excluded features; the size bounds limit how far the search goes
+    pub constraints: FeatureConstraints,
+    pub parsimony: Parsimony,
+    pub hall_of_fame_size: usize,
+}
+
+impl Default for SequentialConfig {
+    fn default() -> Self {
+        Self {
+            direction: SequentialDirection::Forward,
+            floating: false,
+            constraints: FeatureConstraints::default(),
+            parsimony: Parsimony::None,
+            hall_of_fame_size: DEFAULT_HALL_OF_FAME_SIZE,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RfeConfig {
+    // features removed after every fit
+    pub step: usize,
+    // stop once this few features are left
+    pub min_features: usize,
+    // folds used to score every step; at least 2
+    pub k_folds: usize,
+    // seed for the permutation importances of tree models
+    pub seed: Option<u64>,
+}
+
+impl Default for RfeConfig {
+    fn default() -> Self {
+        Self {
+            step: 1,
+            min_features: 1,
+            k_folds: 5,
+            seed: None,
+        }
+    }
+}
+
+// How the temperature of a simulated annealing chain falls with the step `k` of `n`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CoolingSchedule {
+    // T0 * factor^k
+    Geometric { factor: f64 },
+    // T0 * (1 - k / n)
+    Linear,
+    // T0 / (1 + ln(1 + k))
+    Logarithmic,
+}
+
+impl Default for CoolingSchedule {
+    fn default() -> Self {
+        CoolingSchedule::Geometric { factor: 0.99 }
+    }
+}
+
+impl CoolingSchedule {
+    pub fn temperature(&self, initial: f64, step: usize, steps: usize) -> f64 {
+        match *self {
+            CoolingSchedule::Geometric { factor } => initial * factor.powi(step as i32),
+            CoolingSchedule::Linear => initial * (1.0 - step as f64 / steps.max(1) as f64),
+            CoolingSchedule::Logarithmic => initial / (1.0 + (step as f64).ln_1p()),
+        }
+    }
+}
+
+// Settings the stochastic selectors share with a GA run, so they can be compared on the same
+// budget.
+#[derive(Clone, Debug)]
+pub struct SearchSettings {
+    // fitness evaluations the whole search may spend
+    pub evaluations: usize,
+    pub hall_of_fame_size: usize,
+    pub constraints: FeatureConstraints,
+    pub parsimony: Parsimony,
+    pub seed: Option<u64>,
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        Self {
+            evaluations: 2500,
+            hall_of_fame_size: DEFAULT_HALL_OF_FAME_SIZE,
+            constraints: FeatureConstraints::default(),
+            parsimony: Parsimony::None,
+            seed: None,
+        }
+    }
+}
+
+impl SearchSettings {
+    // Same number of evaluations, hall of fame, constraints, penalty and seed as a `run_ga` call
+    // with `gaconfig`.
+    pub fn with_ga_budget(gaconfig: &GaConfig) -> Self {
+        Self {
+            evaluations: gaconfig.populaton_size
+                * gaconfig.islands.max(1)
+                * gaconfig.generations.max(0) as usize,
+            hall_of_fame_size: gaconfig.hall_of_fame_size,
+            constraints: gaconfig.constraints.clone(),
+            parsimony: gaconfig.parsimony,
+            seed: gaconfig.seed,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AnnealingConfig {
+    // independent chains run in parallel; the evaluations are shared between them
+    pub chains: usize,
+    // every move flips between 1 and this many genes
+    pub max_flips: usize,
+    pub cooling: CoolingSchedule,
+    // temperature of the first step in fitness units; `None` calibrates it per chain from a few
+    // random moves so that an average worsening is accepted with probability 0.5
+    pub initial_temperature: Option<f64>,
+    pub search: SearchSettings,
+}
+
+impl Default for AnnealingConfig {
+    fn default() -> Self {
+        Self {
+            chains: 4,
+            max_flips: 1,
+            cooling: CoolingSchedule::default(),
+            initial_temperature: None,
+            search: SearchSettings::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PsoConfig {
+    pub particles: usize,
+    // share of the previous velocity kept every step
+    pub inertia: f64,
+    // pull towards the best position of the particle itself
+    pub cognitive: f64,
+    // pull towards the best position of the swarm
+    pub social: f64,
+    // velocities are clamped to [-max_velocity, max_velocity] so no gene freezes at 0 or 1
+    pub max_velocity: f64,
+    pub search: SearchSettings,
+}
+
+impl Default for PsoConfig {
+    fn default() -> Self {
+        Self {
+            particles: 30,
+            inertia: 0.7,
+            cognitive: 1.5,
+            social: 1.5,
+            max_velocity: 4.0,
+            search: SearchSettings::default(),
+        }
+    }
+}
+
+// What an exhaustive search does when it would evaluate more masks than its budget.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverBudget {
+    // return an error without evaluating anything
+    #[default]
+    Refuse,
+    // print a warning and evaluate every mask anyway
+    Warn,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExhaustiveConfig {
+    // only masks with at most this many features are enumerated
+    pub max_subset_size: Option<usize>,
+    // largest number of masks evaluated without `on_over_budget` kicking in
+    pub budget: u64,
+    pub on_over_budget: OverBudget,
+    // masks evaluated in parallel per batch; every batch is one history entry
+    pub chunk_size: usize,
+    pub constraints: FeatureConstraints,
+    pub parsimony: Parsimony,
+    pub hall_of_fame_size: usize,
+}
+
+impl Default for ExhaustiveConfig {
+    fn default() -> Self {
+        Self {
+            max_subset_size: None,
+            budget: 1 << 20,
+            on_over_budget: OverBudget::Refuse,
+            chunk_size: 4096,
+            constraints: FeatureConstraints::default(),
+            parsimony: Parsimony::None,
+            hall_of_fame_size: DEFAULT_HALL_OF_FAME_SIZE,
+        }
+    }
+}
+
+// How mRMR trades relevance to the target against redundancy with the features already picked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MrmrCriterion {
+    // relevance minus mean redundancy (MID)
+    #[default]
+    Difference,
+    // relevance over mean redundancy (MIQ)
+    Quotient,
+}
+
+#[derive(Clone, Debug)]
+pub struct MrmrConfig {
+    // number of features to pick; `None` ranks every feature
+    pub num_selected: Option<usize>,
+    pub criterion: MrmrCriterion,
+    // neighbours of the mutual information estimates
+    pub neighbours: usize,
+    // treat every distinct target value as a class instead of a continuous target
+    pub classification: bool,
+}
+
+impl Default for MrmrConfig {
+    fn default() -> Self {
+        Self {
+            num_selected: None,
+            criterion: MrmrCriterion::Difference,
+            neighbours: 3,
+            classification: false,
+        }
+    }
+}
+
+// Regularisation of the embedded L1 selector. On standardised features the fit minimises
+// ||y - Xw||^2 / 2n + alpha * l1_ratio * |w|_1 + alpha * (1 - l1_ratio) / 2 * |w|^2.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum L1Penalty {
+    // l1_ratio = 1
+    #[default]
+    Lasso,
+    // share of the penalty that is L1, in (0, 1]; the rest is L2
+    ElasticNet {
+        l1_ratio: f64,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct L1PathConfig {
+    pub penalty: L1Penalty,
+    // regularisation strengths to fit; empty uses `num_alphas` values spaced geometrically from
+    // the smallest alpha that zeroes every coefficient down to 1/1000 of it
+    pub alphas: Vec<f64>,
+    pub num_alphas: usize,
+    // folds used to pick the alpha with the lowest validation MSE
+    pub k_folds: usize,
+    // coordinate descent stops once no coefficient moves by more than this
+    pub tolerance: f64,
+    pub max_iterations: usize,
+}
+
+impl Default for L1PathConfig {
+    fn default() -> Self {
+        Self {
+            penalty: L1Penalty::Lasso,
+            alphas: vec![],
+            num_alphas: 20,
+            k_folds: 5,
+            tolerance: 1e-6,
+            max_iterations: 1000,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BorutaConfig {
+    // forest fits at most; features still undecided afterwards stay tentative
+    pub max_iterations: usize,
+    // significance level of the hit test, Bonferroni-corrected over the undecided features
+    pub p_value: f64,
+    pub seed: Option<u64>,
+}
+
+impl Default for BorutaConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: 100,
+            p_value: 0.01,
+            seed: None,
+        }
+    }
+}
+
+// Where the GA runs of a stability analysis get their data from; the counts add up.
+#[derive(Clone, Debug)]
+pub struct StabilityConfig {
+    // one run on the training part of every fold; 0 or 1 skips
+    pub k_folds: usize,
+    // one run per bootstrap resample of the rows
+    pub bootstraps: usize,
+    // runs on the whole dataset with different seeds
+    pub repeats: usize,
+}
+
+impl Default for StabilityConfig {
+    fn default() -> Self {
+        Self {
+            k_folds: 5,
+            bootstraps: 10,
+            repeats: 5,
         }
     }
 }
@@ -0,0 +1,208 @@
+use rand::seq::SliceRandom;
+
+use crate::{chromosome::Chromosome, ga::GaRng};
+
+// Feature masks the search is allowed to visit: columns that must always or never be used and
+// bounds on how many columns are selected. Features are given by column index.
+#[derive(Clone, Debug, Default)]
+pub struct FeatureConstraints {
+    pub required: Vec<usize>,
+    pub excluded: Vec<usize>,
+    pub min_features: usize,
+    pub max_features: Option<usize>,
+}
+
+impl FeatureConstraints {
+    // Build constraints from column names, as returned by `DataSet::features`.
+    pub fn from_names(
+        feature_names: &[String],
+        required: &[&str],
+        excluded: &[&str],
+    ) -> Result<Self, String> {
+        let index_of = |name: &&str| {
+            feature_names
+                .iter()
+                .position(|feature| feature == name)
+                .ok_or_else(|| format!("unknown feature {:?}", name))
+        };
+        Ok(Self {
+            required: required.iter().map(index_of).collect::<Result<_, _>>()?,
+            excluded: excluded.iter().map(index_of).collect::<Result<_, _>>()?,
+            ..Default::default()
+        })
+    }
+
+    pub fn with_size_bounds(mut self, min_features: usize, max_features: usize) -> Self {
+        self.min_features = min_features;
+        self.max_features = Some(max_features);
+        self
+    }
+
+    // Check that some mask over `num_features` columns satisfies every constraint.
+    pub fn validate(&self, num_features: usize) -> Result<(), String> {
+        if let Some(&index) = self
+            .required
+            .iter()
+            .chain(self.excluded.iter())
+            .find(|&&index| index >= num_features)
+        {
+            return Err(format!(
+                "feature index {} out of range for {} features",
+                index, num_features
+            ));
+        }
+        if let Some(index) = self.required.iter().find(|i| self.excluded.contains(i)) {
+            return Err(format!("feature {} is both required and excluded", index));
+        }
+        let available = num_features - self.excluded.len();
+        let max_features = self.max_features.unwrap_or(num_features);
+        if self.min_features > available {
+            return Err(format!(
+                "at least {} features requested but only {} are not excluded",
+                self.min_features, available
+            ));
+        }
+        if self.required.len() > max_features {
+            return Err(format!(
+                "{} features are required but at most {} may be selected",
+                self.required.len(),
+                max_features
+            ));
+        }
+        if self.min_features > max_features {
+            return Err(format!(
+                "minimum of {} features exceeds the maximum of {}",
+                self.min_features, max_features
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn is_feasible(&self, chromosome: &Chromosome) -> bool {
+        let num_selected = chromosome.count_ones();
+        self.required.iter().all(|&index| chromosome.get(index))
+            && self.excluded.iter().all(|&index| !chromosome.get(index))
+            && num_selected >= self.min_features
+            && self.max_features.is_none_or(|max| num_selected <= max)
+    }
+
+    // Make `chromosome` feasible with as few changes as possible: force the required and
+    // excluded genes, then switch random free genes on or off until the size is within bounds.
+    pub fn repair(&self, chromosome: &mut Chromosome, rng: &mut GaRng) {
+        for &index in &self.required {
+            chromosome.set(index, true);
+        }
+        for &index in &self.excluded {
+            chromosome.set(index, false);
+        }
+
+        let num_selected = chromosome.count_ones();
+        if num_selected < self.min_features {
+            let mut candidates: Vec<usize> = (0..chromosome.len())
+                .filter(|&index| !chromosome.get(index) && !self.excluded.contains(&index))
+                .collect();
+            let (chosen, _) = candidates.partial_shuffle(rng, self.min_features - num_selected);
+            for &index in chosen.iter() {
+                chromosome.set(index, true);
+            }
+        }
+        if let Some(max_features) = self.max_features {
+            if num_selected > max_features {
+                let mut candidates: Vec<usize> = chromosome
+                    .iter_ones()
+                    .filter(|index| !self.required.contains(index))
+                    .collect();
+                let (chosen, _) = candidates.partial_shuffle(rng, num_selected - max_features);
+                for &index in chosen.iter() {
+                    chromosome.set(index, false);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rand::Rng;
+
+    use super::*;
+    use crate::{
+        config::GaConfig,
+        data::{BreastCancerData, DataSet},
+        ga::{new_rng, run_ga},
+        model::ModelName,
+    };
+
+    #[test]
+    fn repaired_masks_are_feasible() {
+        let num_features = 70;
+        let mut rng = new_rng(Some(1));
+        let requireds = [vec![], vec![0], vec![3, 64, 69]];
+        let excludeds = [vec![], vec![1], vec![2, 63, 65, 68]];
+        let bounds = [
+            (0, None),
+            (5, None),
+            (0, Some(4)),
+            (3, Some(3)),
+            (10, Some(60)),
+        ];
+        for required in &requireds {
+            for excluded in &excludeds {
+                for &(min_features, max_features) in &bounds {
+                    let constraints = FeatureConstraints {
+                        required: required.clone(),
+                        excluded: excluded.clone(),
+                        min_features,
+                        max_features,
+                    };
+                    if constraints.validate(num_features).is_err() {
+                        continue;
+                    }
+                    for density in [0.0, 0.05, 0.5, 0.95, 1.0] {
+                        let genes: Vec<bool> =
+                            (0..num_features).map(|_| rng.gen_bool(density)).collect();
+                        let mut mask = Chromosome::from(genes);
+                        constraints.repair(&mut mask, &mut rng);
+                        assert!(
+                            constraints.is_feasible(&mask),
+                            "{:?} {:?}",
+                            constraints,
+                            mask
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ga_keeps_only_feasible_masks() {
+        let constraints = FeatureConstraints {
+            required: vec![0, 7],
+            excluded: vec![1, 2, 3],
+            min_features: 4,
+            max_features: Some(6),
+        };
+        let gaconfig = GaConfig {
+            populaton_size: 12,
+            generations: 4,
+            crossover_rate: 0.5,
+            seed: Some(2),
+            constraints: constraints.clone(),
+            ..GaConfig::default()
+        };
+        let result = run_ga(
+            Arc::new(BreastCancerData::default()),
+            ModelName::LinearRegression,
+            gaconfig,
+        )
+        .unwrap();
+        assert!(result
+            .hall_of_fame
+            .members()
+            .iter()
+            .all(|member| constraints.is_feasible(member.features())));
+    }
+}
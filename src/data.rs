@@ -1,9 +1,11 @@
 use smartcore::{
     dataset::{breast_cancer, Dataset},
-    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    linalg::naive::dense_matrix::DenseMatrix,
     math::num::RealNumber,
 };
-use std::{mem, process::Output};
+use std::mem;
+
+use crate::chromosome::Chromosome;
 use sys_info;
 pub trait DataSet {
     type Output;
@@ -19,7 +21,7 @@ pub trait DataSet {
     fn size(&self) -> usize;
     fn fit_in_memory(&self) -> bool;
     fn target(&self) -> Vec<Self::Input>;
-    fn select_columns(&self, column_selector: &[bool]) -> Option<DenseMatrix<Self::Input>>
+    fn select_columns(&self, column_selector: &Chromosome) -> Option<DenseMatrix<Self::Input>>
     where
         <Self as DataSet>::Input: RealNumber;
 
@@ -27,6 +29,11 @@ pub trait DataSet {
     where
         Self: Sized;
 
+    // dataset made of the given rows, in that order; rows may repeat (bootstrap samples)
+    fn resample(&self, rows: &[usize]) -> Self
+    where
+        Self: Sized;
+
     // fn data(&self) -> Self::DataSetType;
 }
 pub struct BreastCancerData {
@@ -50,10 +57,7 @@ impl DataSet for BreastCancerData {
 
     // (m, n)
     fn dimension(&self) -> (usize, usize) {
-        (
-            self.data.num_samples.clone(),
-            self.data.num_features.clone(),
-        )
+        (self.data.num_samples, self.data.num_features)
     }
 
     // estimate bytes of dataset
@@ -74,12 +78,8 @@ impl DataSet for BreastCancerData {
         false
     }
 
-    fn select_columns(&self, column_selector: &[bool]) -> Option<DenseMatrix<Self::Input>> {
-        let selected_features: Vec<usize> = column_selector
-            .iter()
-            .enumerate()
-            .filter_map(|(index, &feature)| if feature { Some(index) } else { None })
-            .collect();
+    fn select_columns(&self, column_selector: &Chromosome) -> Option<DenseMatrix<Self::Input>> {
+        let selected_features: Vec<usize> = column_selector.iter_ones().collect();
 
         if selected_features.is_empty() {
             return None;
@@ -136,13 +136,15 @@ impl DataSet for BreastCancerData {
         // Create training dataset by excluding the range dedicated to the validation set
         let train_data = BreastCancerData {
             data: Dataset {
+                // `data` is row-major, so the sample of a value is its index over num_features
                 data: self
                     .data
                     .data
                     .iter()
                     .enumerate()
                     .filter_map(|(i, x)| {
-                        if i < start_idx || i >= end_idx {
+                        let row = i / num_features;
+                        if row < start_idx || row >= end_idx {
                             Some(*x)
                         } else {
                             None
@@ -179,7 +181,8 @@ impl DataSet for BreastCancerData {
                     .iter()
                     .enumerate()
                     .filter_map(|(i, x)| {
-                        if i >= start_idx && i < end_idx {
+                        let row = i / num_features;
+                        if row >= start_idx && row < end_idx {
                             Some(*x)
                         } else {
                             None
@@ -210,6 +213,28 @@ impl DataSet for BreastCancerData {
         (train_data, valid_data)
     }
 
+    fn resample(&self, rows: &[usize]) -> Self {
+        let num_features = self.data.num_features;
+        BreastCancerData {
+            data: Dataset {
+                data: rows
+                    .iter()
+                    .flat_map(|&row| {
+                        self.data.data[row * num_features..(row + 1) * num_features]
+                            .iter()
+                            .copied()
+                    })
+                    .collect(),
+                target: rows.iter().map(|&row| self.data.target[row]).collect(),
+                feature_names: self.data.feature_names.clone(),
+                target_names: self.data.target_names.clone(),
+                description: self.data.description.clone(),
+                num_samples: rows.len(),
+                num_features,
+            },
+        }
+    }
+
     // debug
     // fn data(&self) -> Self::DataSetType {
     //    self.data
@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ga::Individual;
+
+// How spread out a population's feature masks are.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct DiversityMetrics {
+    // mean pairwise Hamming distance over the number of features, in [0, 1]
+    pub mean_hamming_distance: f32,
+    // mean per-gene Shannon entropy in bits, in [0, 1]; 0 when every individual agrees on
+    // every gene
+    pub gene_entropy: f32,
+    // distinct masks over population size, in (0, 1]
+    pub unique_fraction: f32,
+}
+
+impl DiversityMetrics {
+    pub fn of(population: &[Individual]) -> Self {
+        Self {
+            mean_hamming_distance: mean_hamming_distance(population),
+            gene_entropy: gene_entropy(population),
+            unique_fraction: unique_fraction(population),
+        }
+    }
+}
+
+// Mean pairwise Hamming distance divided by the number of features: 0.0 when every individual
+// carries the same mask, growing towards 1.0 as the masks disagree.
+pub fn mean_hamming_distance(population: &[Individual]) -> f32 {
+    let num_features = population.first().map_or(0, |first| first.features().len());
+    if population.len() < 2 || num_features == 0 {
+        return 0.0;
+    }
+    let mut total_distance = 0;
+    for (i, a) in population.iter().enumerate() {
+        for b in &population[i + 1..] {
+            total_distance += a.features().hamming_distance(b.features());
+        }
+    }
+    let pairs = population.len() * (population.len() - 1) / 2;
+    total_distance as f32 / (pairs * num_features) as f32
+}
+
+// Binary entropy of every gene's selection frequency, averaged over the genes.
+pub fn gene_entropy(population: &[Individual]) -> f32 {
+    let num_features = population.first().map_or(0, |first| first.features().len());
+    if population.is_empty() || num_features == 0 {
+        return 0.0;
+    }
+    let mut selected = vec![0usize; num_features];
+    for individual in population {
+        for gene in individual.features().iter_ones() {
+            selected[gene] += 1;
+        }
+    }
+    let total: f32 = selected
+        .iter()
+        .map(|&count| {
+            let p = count as f32 / population.len() as f32;
+            if p == 0.0 || p == 1.0 {
+                0.0
+            } else {
+                -p * p.log2() - (1.0 - p) * (1.0 - p).log2()
+            }
+        })
+        .sum();
+    total / num_features as f32
+}
+
+pub fn unique_fraction(population: &[Individual]) -> f32 {
+    if population.is_empty() {
+        return 0.0;
+    }
+    let unique: HashSet<_> = population
+        .iter()
+        .map(|individual| individual.features())
+        .collect();
+    unique.len() as f32 / population.len() as f32
+}
+
+// Population indices ordered by shared fitness, best first. Fitness is shifted to be positive
+// (it is a negative MSE) and divided by the niche count, the summed similarity
+// `1 - (d / radius)^alpha` to every individual within normalised Hamming distance `radius`.
+// Crowded masks are pushed down the order, so distinct good subsets get to reproduce.
+pub fn shared_fitness_order(population: &[Individual], radius: f32, alpha: f32) -> Vec<usize> {
+    let num_features = population
+        .first()
+        .map_or(1, |first| first.features().len().max(1)) as f32;
+    let worst = population
+        .iter()
+        .map(|individual| individual.fitness())
+        .filter(|fitness| fitness.is_finite())
+        .fold(f32::INFINITY, f32::min);
+
+    let shared: Vec<f32> = population
+        .iter()
+        .map(|individual| {
+            if !individual.fitness().is_finite() {
+                return f32::NEG_INFINITY;
+            }
+            let niche_count: f32 = population
+                .iter()
+                .map(|other| {
+                    let distance = individual.features().hamming_distance(other.features()) as f32
+                        / num_features;
+                    if distance < radius {
+                        1.0 - (distance / radius).powf(alpha)
+                    } else {
+                        0.0
+                    }
+                })
+                .sum();
+            // The small offset keeps the worst individual from sharing a zero fitness.
+            (individual.fitness() - worst + f32::EPSILON) / niche_count.max(1.0)
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..population.len()).collect();
+    order.sort_by(|&a, &b| shared[b].total_cmp(&shared[a]));
+    order
+}
@@ -0,0 +1,202 @@
+use std::{iter::Sum, sync::Arc, time::Instant};
+
+use rayon::prelude::*;
+
+use crate::{
+    cache::FitnessCache,
+    chromosome::Chromosome,
+    config::{ExhaustiveConfig, OverBudget},
+    data::DataSet,
+    ga::{evaluate_fitness, finish_selector, GaResult, HallOfFame, Individual, StopReason},
+    model::ModelName,
+    observer::GenerationStats,
+};
+
+// Masks are enumerated as bits of a u64 over the free features.
+const MAX_FREE_FEATURES: usize = 63;
+
+// n choose k, saturating instead of overflowing.
+fn binomial(n: usize, k: usize) -> u128 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    (0..k).fold(1u128, |acc, i| {
+        acc.saturating_mul((n - i) as u128) / (i + 1) as u128
+    })
+}
+
+// Every `bits`-bit number with exactly `ones` bits set, in increasing order (Gosper's hack).
+fn combinations(bits: usize, ones: usize) -> impl Iterator<Item = u64> {
+    let limit = 1u64 << bits;
+    let first = if ones == 0 { 0 } else { (1u64 << ones) - 1 };
+    let mut next = Some(first).filter(|&first| first < limit || ones == 0);
+    std::iter::from_fn(move || {
+        let current = next?;
+        next = if current == 0 {
+            None
+        } else {
+            let lowest = current & current.wrapping_neg();
+            let ripple = current + lowest;
+            let following = (((ripple ^ current) >> 2) / lowest) | ripple;
+            Some(following).filter(|&following| following < limit)
+        };
+        Some(current)
+    })
+}
+
+// Evaluate every feasible non-empty mask, up to `config.max_subset_size` features, and return
+// the exact optimum. Masks are built from the required features plus every combination of the
+// features that are neither required nor excluded, and scored in parallel chunks; only the hall
+// of fame is kept. Refuses (or warns, see `OverBudget`) when there are more masks than
+// `config.budget`. Meant for datasets with up to about 20 features and as ground truth for the
+// heuristic selectors.
+pub fn run_exhaustive<D>(
+    dataset: Arc<D>,
+    model: ModelName,
+    config: ExhaustiveConfig,
+) -> Result<GaResult, String>
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    let num_features = dataset.dimension().1;
+    config.constraints.validate(num_features)?;
+    let constraints = &config.constraints;
+    let free: Vec<usize> = (0..num_features)
+        .filter(|gene| !constraints.required.contains(gene) && !constraints.excluded.contains(gene))
+        .collect();
+    if free.len() > MAX_FREE_FEATURES {
+        return Err(format!(
+            "{} free features cannot be enumerated, at most {} are supported",
+            free.len(),
+            MAX_FREE_FEATURES
+        ));
+    }
+    let mut required = Chromosome::new(num_features);
+    for &gene in &constraints.required {
+        required.set(gene, true);
+    }
+    let num_required = required.count_ones();
+    let max_size = constraints
+        .max_features
+        .unwrap_or(num_features)
+        .min(config.max_subset_size.unwrap_or(num_features))
+        .min(num_required + free.len());
+    let min_size = constraints.min_features.max(num_required).max(1);
+    if min_size > max_size {
+        return Err(format!(
+            "no mask has between {} and {} features",
+            min_size, max_size
+        ));
+    }
+
+    let sizes = (min_size - num_required)..=(max_size - num_required);
+    let total: u128 = sizes
+        .clone()
+        .map(|ones| binomial(free.len(), ones))
+        .fold(0, u128::saturating_add);
+    if total > config.budget as u128 {
+        let message = format!(
+            "exhaustive search needs {} evaluations, more than the budget of {}",
+            total, config.budget
+        );
+        match config.on_over_budget {
+            OverBudget::Refuse => return Err(message),
+            OverBudget::Warn => eprintln!("Warning: {}", message),
+        }
+    }
+
+    let mut hall_of_fame = HallOfFame::new(config.hall_of_fame_size.max(1));
+    let mut history = vec![];
+    let mut evaluations = 0;
+    let started = Instant::now();
+    let mut masks = sizes
+        .flat_map(|ones| combinations(free.len(), ones))
+        .map(|bits| {
+            let mut mask = required.clone();
+            for (position, &gene) in free.iter().enumerate() {
+                if bits >> position & 1 == 1 {
+                    mask.set(gene, true);
+                }
+            }
+            mask
+        })
+        .peekable();
+    while masks.peek().is_some() {
+        let chunk: Vec<Chromosome> = masks.by_ref().take(config.chunk_size.max(1)).collect();
+        evaluations += chunk.len();
+        // every mask comes up once, so a fitness cache would only hold on to all of them
+        let evaluated: Vec<Individual> = chunk
+            .into_par_iter()
+            .map(|mask| {
+                let mut individual = Individual::from_features(mask);
+                let negative_mse = evaluate_fitness(&individual, dataset.as_ref(), model.clone());
+                individual.set_score(negative_mse, config.parsimony);
+                individual
+            })
+            .collect();
+        hall_of_fame.update(&evaluated);
+        history.push(GenerationStats::from_population(
+            0,
+            history.len() + 1,
+            &evaluated,
+            started.elapsed(),
+            evaluations,
+        ));
+    }
+
+    Ok(finish_selector(
+        hall_of_fame,
+        history,
+        StopReason::Completed,
+        // nothing was cached
+        &FitnessCache::new(),
+        num_features,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binomial_small_values() {
+        assert_eq!(binomial(5, 0), 1);
+        assert_eq!(binomial(5, 2), 10);
+        assert_eq!(binomial(5, 5), 1);
+        assert_eq!(binomial(3, 4), 0);
+        assert_eq!(binomial(63, 31), 916_312_070_471_295_267);
+    }
+
+    #[test]
+    fn combinations_match_binomial() {
+        for bits in [0, 1, 5, 12] {
+            for ones in 0..=bits {
+                let masks: Vec<u64> = combinations(bits, ones).collect();
+                assert_eq!(
+                    masks.len() as u128,
+                    binomial(bits, ones),
+                    "{} of {}",
+                    ones,
+                    bits
+                );
+                assert!(masks.windows(2).all(|pair| pair[0] < pair[1]));
+                assert!(masks
+                    .iter()
+                    .all(|&mask| mask.count_ones() as usize == ones && mask >> bits == 0));
+            }
+        }
+    }
+
+    #[test]
+    fn combinations_reach_the_top_bit() {
+        let masks: Vec<u64> = combinations(MAX_FREE_FEATURES, 1).collect();
+        assert_eq!(masks.len(), MAX_FREE_FEATURES);
+        assert_eq!(masks.last(), Some(&(1 << (MAX_FREE_FEATURES - 1))));
+        assert_eq!(
+            combinations(MAX_FREE_FEATURES, MAX_FREE_FEATURES).count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,438 @@
+use num_traits::ToPrimitive;
+use rayon::prelude::*;
+use smartcore::linalg::BaseMatrix;
+
+use crate::{
+    chromosome::Chromosome,
+    config::{MrmrConfig, MrmrCriterion},
+    data::DataSet,
+};
+
+// Columns of the dataset as f64, one vector per feature.
+pub(crate) fn columns<D: DataSet>(dataset: &D) -> Vec<Vec<f64>> {
+    let num_features = dataset.dimension().1;
+    match dataset.select_columns(&Chromosome::new_all_true(num_features)) {
+        Some(matrix) => (0..num_features)
+            .map(|col| {
+                matrix
+                    .get_col_as_vec(col)
+                    .iter()
+                    .map(|x| x.to_f64().unwrap_or(0.0))
+                    .collect()
+            })
+            .collect(),
+        None => vec![],
+    }
+}
+
+pub(crate) fn target<D: DataSet>(dataset: &D) -> Vec<f64> {
+    dataset
+        .target()
+        .iter()
+        .map(|y| y.to_f64().unwrap_or(0.0))
+        .collect()
+}
+
+// Pearson correlation; 0.0 when either side is constant.
+pub(crate) fn pearson(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+    let (mut covariance, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (a, b) in x.iter().zip(y.iter()) {
+        covariance += (a - mean_x) * (b - mean_y);
+        var_x += (a - mean_x) * (a - mean_x);
+        var_y += (b - mean_y) * (b - mean_y);
+    }
+    if var_x == 0.0 || var_y == 0.0 {
+        return 0.0;
+    }
+    covariance / (var_x * var_y).sqrt()
+}
+
+// Feature indices ordered by absolute Pearson correlation with the target, strongest first.
+pub fn correlation_ranking<D: DataSet>(dataset: &D) -> Vec<usize> {
+    let target = target(dataset);
+    let scores: Vec<f64> = columns(dataset)
+        .iter()
+        .map(|column| pearson(column, &target).abs())
+        .collect();
+    let mut ranking: Vec<usize> = (0..scores.len()).collect();
+    ranking.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+    ranking
+}
+
+// Pair every score with its feature name, best first; NaN scores go last.
+fn ranked<D: DataSet>(dataset: &D, scores: Vec<f64>) -> Vec<(String, f64)> {
+    let mut ranked: Vec<(String, f64)> = dataset.features().into_iter().zip(scores).collect();
+    let key = |score: f64| {
+        if score.is_nan() {
+            f64::NEG_INFINITY
+        } else {
+            score
+        }
+    };
+    ranked.sort_by(|a, b| key(b.1).total_cmp(&key(a.1)));
+    ranked
+}
+
+// Column indices in the order of a ranked list, for `Initialization::Ranked` and friends.
+pub fn ranking_indices<D: DataSet>(dataset: &D, ranked: &[(String, f64)]) -> Vec<usize> {
+    let names = dataset.features();
+    ranked
+        .iter()
+        .filter_map(|(name, _)| names.iter().position(|feature| feature == name))
+        .collect()
+}
+
+fn variance(x: &[f64]) -> f64 {
+    let n = x.len().max(1) as f64;
+    let mean = x.iter().sum::<f64>() / n;
+    x.iter().map(|a| (a - mean) * (a - mean)).sum::<f64>() / n
+}
+
+// Class of every sample, with one class per distinct target value.
+fn class_labels(target: &[f64]) -> (Vec<usize>, usize) {
+    let mut values = target.to_vec();
+    values.sort_by(f64::total_cmp);
+    values.dedup();
+    let labels = target
+        .iter()
+        .map(|y| values.binary_search_by(|v| v.total_cmp(y)).unwrap_or(0))
+        .collect();
+    (labels, values.len())
+}
+
+// Ranks starting at 1, ties get the mean of the ranks they span.
+fn average_ranks(x: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..x.len()).collect();
+    order.sort_by(|&a, &b| x[a].total_cmp(&x[b]));
+    let mut ranks = vec![0.0; x.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && x[order[end]] == x[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+// Features whose population variance exceeds `threshold`, highest variance first. Constant
+// columns carry no information for any model.
+pub fn variance_threshold<D: DataSet>(dataset: &D, threshold: f64) -> Vec<(String, f64)> {
+    let scores = columns(dataset)
+        .iter()
+        .map(|column| variance(column))
+        .collect();
+    let mut ranked = ranked(dataset, scores);
+    ranked.retain(|(_, score)| *score > threshold);
+    ranked
+}
+
+// Absolute Pearson correlation with the target.
+pub fn pearson_scores<D: DataSet>(dataset: &D) -> Vec<(String, f64)> {
+    let target = target(dataset);
+    let scores = columns(dataset)
+        .iter()
+        .map(|column| pearson(column, &target).abs())
+        .collect();
+    ranked(dataset, scores)
+}
+
+// Absolute Spearman rank correlation with the target; also catches monotonic non-linear links.
+pub fn spearman_scores<D: DataSet>(dataset: &D) -> Vec<(String, f64)> {
+    let target = average_ranks(&target(dataset));
+    let scores = columns(dataset)
+        .iter()
+        .map(|column| pearson(&average_ranks(column), &target).abs())
+        .collect();
+    ranked(dataset, scores)
+}
+
+// One-way ANOVA F statistic of every feature across the classes of the target: between-class
+// over within-class variance. Every distinct target value is a class.
+pub fn anova_f_scores<D: DataSet>(dataset: &D) -> Vec<(String, f64)> {
+    let (labels, num_classes) = class_labels(&target(dataset));
+    let scores = columns(dataset)
+        .iter()
+        .map(|column| {
+            let n = column.len();
+            if num_classes < 2 || n <= num_classes {
+                return 0.0;
+            }
+            let mut sums = vec![0.0; num_classes];
+            let mut counts = vec![0usize; num_classes];
+            for (&x, &label) in column.iter().zip(&labels) {
+                sums[label] += x;
+                counts[label] += 1;
+            }
+            let mean = column.iter().sum::<f64>() / n as f64;
+            let between: f64 = (0..num_classes)
+                .map(|c| {
+                    let class_mean = sums[c] / counts[c] as f64;
+                    counts[c] as f64 * (class_mean - mean) * (class_mean - mean)
+                })
+                .sum();
+            let within: f64 = column
+                .iter()
+                .zip(&labels)
+                .map(|(&x, &label)| {
+                    let class_mean = sums[label] / counts[label] as f64;
+                    (x - class_mean) * (x - class_mean)
+                })
+                .sum();
+            let between = between / (num_classes - 1) as f64;
+            let within = within / (n - num_classes) as f64;
+            if within == 0.0 {
+                if between == 0.0 {
+                    0.0
+                } else {
+                    f64::INFINITY
+                }
+            } else {
+                between / within
+            }
+        })
+        .collect();
+    ranked(dataset, scores)
+}
+
+// Chi-squared statistic between every feature and the classes of the target, treating the
+// feature values as counts (or frequencies). Fails on negative values.
+pub fn chi2_scores<D: DataSet>(dataset: &D) -> Result<Vec<(String, f64)>, String> {
+    let (labels, num_classes) = class_labels(&target(dataset));
+    let columns = columns(dataset);
+    let names = dataset.features();
+    let n = labels.len() as f64;
+    let mut class_counts = vec![0.0; num_classes];
+    for &label in &labels {
+        class_counts[label] += 1.0;
+    }
+    let mut scores = Vec::with_capacity(columns.len());
+    for (col, column) in columns.iter().enumerate() {
+        if column.iter().any(|&x| x < 0.0) {
+            return Err(format!(
+                "chi-squared needs non-negative features, {} has negative values",
+                names.get(col).map_or("a column", |name| name.as_str())
+            ));
+        }
+        let mut observed = vec![0.0; num_classes];
+        for (&x, &label) in column.iter().zip(&labels) {
+            observed[label] += x;
+        }
+        let total: f64 = observed.iter().sum();
+        let score: f64 = (0..num_classes)
+            .map(|c| {
+                let expected = total * class_counts[c] / n;
+                if expected == 0.0 {
+                    0.0
+                } else {
+                    (observed[c] - expected) * (observed[c] - expected) / expected
+                }
+            })
+            .sum();
+        scores.push(score);
+    }
+    Ok(ranked(dataset, scores))
+}
+
+// Digamma function: recurrence up to x >= 6, then the asymptotic series.
+fn digamma(mut x: f64) -> f64 {
+    let mut result = 0.0;
+    while x < 6.0 {
+        result -= 1.0 / x;
+        x += 1.0;
+    }
+    let inv = 1.0 / x;
+    let inv2 = inv * inv;
+    result + x.ln()
+        - 0.5 * inv
+        - inv2 * (1.0 / 12.0 - inv2 * (1.0 / 120.0 - inv2 * (1.0 / 252.0 - inv2 / 240.0)))
+}
+
+// Distance to the k-th nearest of `distances` (self excluded by the caller).
+fn kth_smallest(distances: &mut [f64], k: usize) -> f64 {
+    let k = k.clamp(1, distances.len()) - 1;
+    *distances.select_nth_unstable_by(k, f64::total_cmp).1
+}
+
+fn standardized(x: &[f64]) -> Vec<f64> {
+    let n = x.len().max(1) as f64;
+    let mean = x.iter().sum::<f64>() / n;
+    let std = variance(x).sqrt();
+    if std == 0.0 {
+        return vec![0.0; x.len()];
+    }
+    x.iter().map(|a| (a - mean) / std).collect()
+}
+
+// Kraskov–Stögbauer–Grassberger estimate (algorithm 1) of the mutual information in nats
+// between two continuous variables.
+fn ksg_mutual_information(x: &[f64], y: &[f64], k: usize) -> f64 {
+    let n = x.len();
+    if n <= k {
+        return 0.0;
+    }
+    let mut neighbour_terms = 0.0;
+    let mut distances = Vec::with_capacity(n - 1);
+    for i in 0..n {
+        distances.clear();
+        distances.extend(
+            (0..n)
+                .filter(|&j| j != i)
+                .map(|j| (x[i] - x[j]).abs().max((y[i] - y[j]).abs())),
+        );
+        let radius = kth_smallest(&mut distances, k);
+        // with k or more exact duplicates the radius is 0 and the duplicates are counted
+        let within = |distance: f64| distance < radius || distance == 0.0;
+        let nx = x.iter().filter(|&&xj| within((x[i] - xj).abs())).count();
+        let ny = y.iter().filter(|&&yj| within((y[i] - yj).abs())).count();
+        // the counts include the point itself, matching psi(n_x + 1)
+        neighbour_terms += digamma(nx as f64) + digamma(ny as f64);
+    }
+    (digamma(n as f64) + digamma(k as f64) - neighbour_terms / n as f64).max(0.0)
+}
+
+// Ross (2014) estimate of the mutual information in nats between a continuous variable and
+// class labels. Samples alone in their class are skipped.
+fn ross_mutual_information(x: &[f64], labels: &[usize], num_classes: usize, k: usize) -> f64 {
+    let mut class_sizes = vec![0usize; num_classes];
+    for &label in labels {
+        class_sizes[label] += 1;
+    }
+    let (mut count, mut total) = (0usize, 0.0);
+    let mut distances = vec![];
+    for i in 0..x.len() {
+        let class_size = class_sizes[labels[i]];
+        if class_size < 2 {
+            continue;
+        }
+        let k = k.min(class_size - 1);
+        distances.clear();
+        distances.extend(
+            (0..x.len())
+                .filter(|&j| j != i && labels[j] == labels[i])
+                .map(|j| (x[i] - x[j]).abs()),
+        );
+        let radius = kth_smallest(&mut distances, k);
+        let m = x
+            .iter()
+            .filter(|&&xj| {
+                let distance = (x[i] - xj).abs();
+                distance < radius || distance == 0.0
+            })
+            .count();
+        total += digamma(k as f64) - digamma(class_size as f64) - digamma(m as f64);
+        count += 1;
+    }
+    if count == 0 {
+        return 0.0;
+    }
+    (digamma(count as f64) + total / count as f64).max(0.0)
+}
+
+// Mutual information between every feature and a continuous target, estimated from the `k`
+// nearest neighbours (3 is the usual choice). Features and target are standardised first.
+// Catches non-monotonic dependencies the correlation scores miss.
+pub fn mutual_information_regression<D>(dataset: &D, k: usize) -> Vec<(String, f64)>
+where
+    D: DataSet + Sync,
+{
+    let target = standardized(&target(dataset));
+    let scores = columns(dataset)
+        .par_iter()
+        .map(|column| ksg_mutual_information(&standardized(column), &target, k.max(1)))
+        .collect();
+    ranked(dataset, scores)
+}
+
+// Mutual information between every feature and the classes of the target, estimated from the
+// `k` nearest neighbours within each class.
+pub fn mutual_information_classification<D>(dataset: &D, k: usize) -> Vec<(String, f64)>
+where
+    D: DataSet + Sync,
+{
+    let (labels, num_classes) = class_labels(&target(dataset));
+    let scores = columns(dataset)
+        .par_iter()
+        .map(|column| {
+            ross_mutual_information(&standardized(column), &labels, num_classes, k.max(1))
+        })
+        .collect();
+    ranked(dataset, scores)
+}
+
+// Minimum redundancy maximum relevance: greedily pick the feature with the best trade-off
+// between its mutual information with the target and its mean mutual information with the
+// features picked so far. Returns the features in the order they were picked, each with its
+// criterion value at that point; cut it at k or pass `ranking_indices` of it to
+// `Initialization::Ranked` to seed the GA.
+pub fn mrmr<D>(dataset: &D, config: &MrmrConfig) -> Vec<(String, f64)>
+where
+    D: DataSet + Sync,
+{
+    let k = config.neighbours.max(1);
+    let columns: Vec<Vec<f64>> = columns(dataset)
+        .iter()
+        .map(|column| standardized(column))
+        .collect();
+    let relevance: Vec<f64> = if config.classification {
+        let (labels, num_classes) = class_labels(&target(dataset));
+        columns
+            .par_iter()
+            .map(|column| ross_mutual_information(column, &labels, num_classes, k))
+            .collect()
+    } else {
+        let target = standardized(&target(dataset));
+        columns
+            .par_iter()
+            .map(|column| ksg_mutual_information(column, &target, k))
+            .collect()
+    };
+
+    let names = dataset.features();
+    let num_selected = config
+        .num_selected
+        .unwrap_or(columns.len())
+        .min(columns.len());
+    let mut redundancy_sums = vec![0.0; columns.len()];
+    let mut remaining: Vec<usize> = (0..columns.len()).collect();
+    let mut picked: Vec<(String, f64)> = Vec::with_capacity(num_selected);
+    while picked.len() < num_selected {
+        let score = |feature: usize| {
+            if picked.is_empty() {
+                return relevance[feature];
+            }
+            let redundancy = redundancy_sums[feature] / picked.len() as f64;
+            match config.criterion {
+                MrmrCriterion::Difference => relevance[feature] - redundancy,
+                MrmrCriterion::Quotient => relevance[feature] / redundancy.max(f64::EPSILON),
+            }
+        };
+        let Some((position, value)) = remaining
+            .iter()
+            .enumerate()
+            .map(|(position, &feature)| (position, score(feature)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            break;
+        };
+        let chosen = remaining.swap_remove(position);
+        picked.push((names[chosen].clone(), value));
+
+        let added: Vec<f64> = remaining
+            .par_iter()
+            .map(|&feature| ksg_mutual_information(&columns[feature], &columns[chosen], k))
+            .collect();
+        for (&feature, redundancy) in remaining.iter().zip(added) {
+            redundancy_sums[feature] += redundancy;
+        }
+    }
+    picked
+}
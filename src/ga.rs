@@ -3,216 +3,1311 @@ extern crate rayon;
 extern crate smartcore;
 
 use std::{
+    collections::HashSet,
+    fmt,
     iter::Sum,
-    sync::{Arc, Mutex},
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use rayon::iter::{
-     IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator,
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator,
 };
+use serde::{Deserialize, Serialize};
 use smartcore::{
     ensemble::random_forest_regressor::RandomForestRegressor,
-    linear::linear_regression::LinearRegression
+    linear::linear_regression::LinearRegression,
 };
 
 use crate::{
-    config::GaConfig,
+    cache::{CacheStats, FitnessCache},
+    checkpoint::{data_fingerprint, fold_checkpoint_path, GaCheckpoint},
+    chromosome::Chromosome,
+    config::{DuplicateHandling, GaConfig, Initialization, MigrationTopology, Niching, Parsimony},
+    constraints::FeatureConstraints,
     data::DataSet,
+    diversity::{mean_hamming_distance, shared_fitness_order},
     model::ModelName,
+    observer::{GenerationStats, ObserverAction},
 };
 
-#[derive(Clone, Debug)]
+// RNG threaded through every random GA step. Each island owns its stream and no draw depends
+// on how rayon schedules work, so a seeded run is reproducible regardless of the thread count.
+pub type GaRng = ChaCha8Rng;
+
+pub fn new_rng(seed: Option<u64>) -> GaRng {
+    match seed {
+        Some(seed) => GaRng::seed_from_u64(seed),
+        None => GaRng::from_entropy(),
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Individual {
-    features: Vec<bool>,
+    features: Chromosome,
+    // score the GA maximises: negative MSE minus the parsimony penalty
     fitness: f32,
+    // raw MSE of the model trained on `features`
+    error: f32,
 }
 
 impl Individual {
     // new random individual
-    fn new(num_features: usize) -> Self {
-        let mut rng = rand::thread_rng();
+    pub(crate) fn new(num_features: usize, rng: &mut GaRng) -> Self {
+        let blocks = (0..num_features.div_ceil(64)).map(|_| rng.gen()).collect();
         Self {
-            features: (0..num_features).map(|_| rng.gen()).collect(),
+            features: Chromosome::from_blocks(num_features, blocks),
             fitness: 0.0,
+            error: 0.0,
+        }
+    }
+
+    // every feature selected independently with probability `density`
+    fn new_with_density(num_features: usize, density: f64, rng: &mut GaRng) -> Self {
+        let mut features = Chromosome::new(num_features);
+        for gene in 0..num_features {
+            if rng.gen_bool(density) {
+                features.set(gene, true);
+            }
+        }
+        Self::from_features(features)
+    }
+
+    // features near the top of `ranking` are more likely to be selected
+    fn new_ranked(num_features: usize, ranking: &[usize], density: f64, rng: &mut GaRng) -> Self {
+        let mut features = Chromosome::new(num_features);
+        let ranked = ranking.len() as f64;
+        for (rank, &gene) in ranking.iter().enumerate() {
+            let probability = (2.0 * density * (1.0 - rank as f64 / ranked)).clamp(0.0, 1.0);
+            if gene < num_features && rng.gen_bool(probability) {
+                features.set(gene, true);
+            }
         }
+        Self::from_features(features)
     }
 
     // select all features
     pub fn new_all_true(num_features: usize) -> Self {
         Self {
-            features: vec![true; num_features],
+            features: Chromosome::new_all_true(num_features),
+            fitness: 0.0,
+            error: 0.0,
+        }
+    }
+
+    // individual carrying a given feature mask, not evaluated yet
+    pub fn from_features(features: Chromosome) -> Self {
+        Self {
+            features,
+            fitness: 0.0,
+            error: 0.0,
+        }
+    }
+
+    pub fn features(&self) -> &Chromosome {
+        &self.features
+    }
+
+    pub fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    pub fn error(&self) -> f32 {
+        self.error
+    }
+
+    // Set the raw and penalized scores from the negative MSE returned by `evaluate_fitness`. A
+    // mask without any feature cannot be trained and gets the worst possible score.
+    pub(crate) fn set_score(&mut self, negative_mse: f32, parsimony: Parsimony) {
+        let num_selected = self.features.count_ones();
+        if num_selected == 0 {
+            self.error = f32::INFINITY;
+            self.fitness = f32::NEG_INFINITY;
+            return;
+        }
+        self.error = -negative_mse;
+        self.fitness = negative_mse - parsimony.penalty(num_selected);
+    }
+
+    // uniform crossover: every gene is taken from either parent with equal probability
+    pub(crate) fn crossover(&self, other: &Individual, rng: &mut GaRng) -> Self {
+        let blocks = self
+            .features
+            .blocks()
+            .iter()
+            .zip(other.features.blocks())
+            .map(|(&a, &b)| {
+                let from_self: u64 = rng.gen();
+                (a & from_self) | (b & !from_self)
+            })
+            .collect();
+        Self {
+            features: Chromosome::from_blocks(self.features.len(), blocks),
             fitness: 0.0,
+            error: 0.0,
         }
     }
 
-    fn mutate(&mut self) {
-        let mut rng = rand::thread_rng();
-        for gene in self.features.iter_mut() {
-            if rng.gen_bool(0.01) {
-                *gene = !*gene;
+    // Force the mask back into the feasible region after initialization, crossover or mutation.
+    pub(crate) fn repair(&mut self, constraints: &FeatureConstraints, rng: &mut GaRng) {
+        constraints.repair(&mut self.features, rng);
+    }
+
+    pub(crate) fn mutate(&mut self, rng: &mut GaRng) {
+        self.mutate_with_rate(0.01, rng);
+    }
+
+    // flip every gene independently with probability `rate`
+    fn mutate_with_rate(&mut self, rate: f64, rng: &mut GaRng) {
+        for gene in 0..self.features.len() {
+            if rng.gen_bool(rate) {
+                self.features.flip(gene);
             }
         }
     }
 }
 
+// Fitness (negative MSE) of `individual` with the model trained and scored on all of `dataset`.
+// An empty mask or a failed fit scores `f32::NEG_INFINITY`, never a perfect 0.0.
 pub fn evaluate_fitness<D>(individual: &Individual, dataset: &D, model: ModelName) -> f32
 where
     D: DataSet, // DataSet trait is assumed to provide select_columns and target methods.
     D::Input: std::ops::Sub<Output = D::Input>,
     f32: Sum<<D as DataSet>::Input>, // Model trait as defined previously.
 {
-    match model {
+    evaluate_fitness_on_split(individual, dataset, dataset, model)
+}
+
+// Like `evaluate_fitness`, but the model is trained on `train` and scored on the held-out `valid`.
+// An empty mask or a failed fit scores `f32::NEG_INFINITY`, never a perfect 0.0.
+pub fn evaluate_fitness_on_split<D>(
+    individual: &Individual,
+    train: &D,
+    valid: &D,
+    model: ModelName,
+) -> f32
+where
+    D: DataSet,
+    D::Input: std::ops::Sub<Output = D::Input>,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    let (Some(x_train), Some(x_valid)) = (
+        train.select_columns(&individual.features),
+        valid.select_columns(&individual.features),
+    ) else {
+        return f32::NEG_INFINITY;
+    };
+    let y_train = train.target();
+    let actual = valid.target();
+    let predictions = match model {
         ModelName::LinearRegression => {
-            // Select features based on the individual's characteristics
-            if let Some(x_selected) = dataset.select_columns(&individual.features) {
-                // Prepare the target data from the dataset
-                let actual = dataset.target(); // Assuming this returns Vec<f64>, matching the DataSet trait
-
-                // Create an instance of Linear Regression from SmartCore
-                let lr = LinearRegression::fit(&x_selected, &actual, Default::default()).unwrap();
-
-                // Predict outcomes using the same selected features
-                if let Ok(predictions) = lr.predict(&x_selected) {
-                    // Evaluate the model's predictions against the actual outcomes
-                    let mse: f32 = actual
-                        .iter()
-                        .zip(predictions.iter())
-                        .map(|(a, p)| (*a - *p) * (*a - *p)) // Subtracting actual from predicted values
-                        .sum::<f32>()
-                        / actual.len() as f32;
-                    return -mse;
-                }
+            LinearRegression::fit(&x_train, &y_train, Default::default())
+                .and_then(|lr| lr.predict(&x_valid))
+        }
+        ModelName::DecisionTreeRegressor => {
+            RandomForestRegressor::fit(&x_train, &y_train, Default::default())
+                .and_then(|dt| dt.predict(&x_valid))
+        }
+    };
+    match predictions {
+        Ok(predictions) => {
+            let mse: f32 = actual
+                .iter()
+                .zip(predictions.iter())
+                .map(|(a, p)| (*a - *p) * (*a - *p))
+                .sum::<f32>()
+                / actual.len() as f32;
+            -mse
+        }
+        Err(_) => f32::NEG_INFINITY,
+    }
+}
 
-                // // Attempt to fit the model with the selected features and the actual outcomes
-                // if lr.fit().is_ok() {
+// Mean held-out fitness of `individual` over `k_folds` cross-validation folds, run in parallel;
+// `f32::NEG_INFINITY` when the mask is empty or any fold fails to fit.
+pub fn cross_validated_fitness<D>(
+    individual: &Individual,
+    dataset: &D,
+    model: ModelName,
+    k_folds: usize,
+) -> f32
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    let total: f32 = (0..k_folds)
+        .into_par_iter()
+        .map(|fold| {
+            let (train, valid) = dataset.split_for_cross_validation(k_folds, fold);
+            evaluate_fitness_on_split(individual, &train, &valid, model.clone())
+        })
+        .sum();
+    total / k_folds as f32
+}
+
+// `evaluate_fitness`, but masks already scored on this fold are looked up instead of retrained.
+pub fn evaluate_fitness_cached<D>(
+    individual: &Individual,
+    dataset: &D,
+    model: ModelName,
+    cache: &FitnessCache,
+    fold: usize,
+) -> f32
+where
+    D: DataSet,
+    D::Input: std::ops::Sub<Output = D::Input>,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    cache.get_or_insert_with(fold, &individual.features, || {
+        evaluate_fitness(individual, dataset, model)
+    })
+}
+
+// Score every mask in parallel through `cache`, for the selectors that build their candidates
+// themselves rather than evolving a population.
+pub(crate) fn evaluate_masks<D>(
+    masks: Vec<Chromosome>,
+    dataset: &D,
+    model: &ModelName,
+    cache: &FitnessCache,
+    parsimony: Parsimony,
+) -> Vec<Individual>
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    masks
+        .into_par_iter()
+        .map(|mask| {
+            let mut individual = Individual::from_features(mask);
+            let negative_mse =
+                evaluate_fitness_cached(&individual, dataset, model.clone(), cache, 0);
+            individual.set_score(negative_mse, parsimony);
+            individual
+        })
+        .collect()
+}
 
-                // }
+// Best distinct feature sets seen over a whole run, ordered by fitness (best first).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HallOfFame {
+    capacity: usize,
+    members: Vec<Individual>,
+}
+
+impl HallOfFame {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            members: Vec::with_capacity(capacity),
+        }
+    }
+
+    // Offer every evaluated individual of a generation; duplicates of a feature set already
+    // in the hall are ignored so the hall only holds distinct masks.
+    pub fn update(&mut self, population: &[Individual]) {
+        for individual in population {
+            if self
+                .members
+                .iter()
+                .any(|member| member.features == individual.features)
+            {
+                continue;
             }
-            0.0 // Default return value in case of any failure
+            self.members.push(individual.clone());
         }
-        ModelName::DecisionTreeRegressor => {
-            if let Some(x_selected) = dataset.select_columns(&individual.features) {
-                let actual = dataset.target();
-                let dt =
-                    RandomForestRegressor::fit(&x_selected, &actual, Default::default()).unwrap();
-                if let Ok(predictions) = dt.predict(&x_selected) {
-                    let mse: f32 = actual
-                        .iter()
-                        .zip(predictions.iter())
-                        .map(|(a, p)| (*a - *p) * (*a - *p))
-                        .sum::<f32>()
-                        / actual.len() as f32;
-                    return -mse;
-                }
+        sort_by_fitness(&mut self.members);
+        self.members.truncate(self.capacity);
+    }
+
+    pub fn best(&self) -> Option<&Individual> {
+        self.members.first()
+    }
+
+    pub fn members(&self) -> &[Individual] {
+        &self.members
+    }
+}
+
+// Sort descending so the fittest individual comes first; equal fitness prefers fewer features.
+pub(crate) fn sort_by_fitness(population: &mut [Individual]) {
+    population.sort_by(|a, b| {
+        b.fitness
+            .partial_cmp(&a.fitness)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.features.count_ones().cmp(&b.features.count_ones()))
+    });
+}
+
+// Why a GA run ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StopReason {
+    // all `generations` were run
+    MaxGenerations,
+    // the best fitness did not improve for `stall_generations` generations
+    Stalled,
+    // the best fitness reached `target_fitness`
+    TargetReached,
+    // population diversity fell below `min_diversity`
+    DiversityCollapsed,
+    // the run exceeded `time_budget`
+    TimeBudgetExhausted,
+    // an observer asked the run to stop
+    ObserverRequested,
+    // a deterministic selector visited every step it plans to
+    Completed,
+    // a stochastic selector used up its evaluation budget
+    BudgetExhausted,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            StopReason::MaxGenerations => "maximum number of generations reached",
+            StopReason::Stalled => "no improvement within the stall window",
+            StopReason::TargetReached => "target fitness reached",
+            StopReason::DiversityCollapsed => "population diversity below threshold",
+            StopReason::TimeBudgetExhausted => "time budget exhausted",
+            StopReason::ObserverRequested => "stopped by an observer",
+            StopReason::Completed => "search completed",
+            StopReason::BudgetExhausted => "evaluation budget exhausted",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+// Everything a GA run produces besides the printed summary.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GaResult {
+    pub best: Individual,
+    pub hall_of_fame: HallOfFame,
+    pub stop_reason: StopReason,
+    // generations whose population was evaluated
+    pub generations_run: usize,
+    // one entry per evaluated generation, oldest first
+    pub history: Vec<GenerationStats>,
+    // fitness cache at the end of the run; folds of a cross-validation share one cache
+    pub cache_stats: CacheStats,
+}
+
+impl GaResult {
+    // The per-generation history as a JSON array, for plotting convergence elsewhere.
+    pub fn history_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.history).map_err(|e| e.to_string())
+    }
+}
+
+// Tracks the stopping criteria of `GaConfig` across generations.
+struct StopCheck {
+    started: Instant,
+    // time spent before the run was resumed from a checkpoint
+    elapsed_before: Duration,
+    best_fitness: f32,
+    generations_without_improvement: usize,
+}
+
+impl StopCheck {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            elapsed_before: Duration::ZERO,
+            best_fitness: f32::NEG_INFINITY,
+            generations_without_improvement: 0,
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.elapsed_before + self.started.elapsed()
+    }
+
+    // Called once per generation with the evaluated population, sorted best first.
+    fn check(&mut self, gaconfig: &GaConfig, population: &[Individual]) -> Option<StopReason> {
+        let generation_best = population
+            .first()
+            .map_or(f32::NEG_INFINITY, |best| best.fitness);
+        if generation_best > self.best_fitness {
+            self.best_fitness = generation_best;
+            self.generations_without_improvement = 0;
+        } else {
+            self.generations_without_improvement += 1;
+        }
+
+        if let Some(target) = gaconfig.target_fitness {
+            if self.best_fitness >= target {
+                return Some(StopReason::TargetReached);
+            }
+        }
+        if let Some(stall) = gaconfig.stall_generations {
+            if self.generations_without_improvement >= stall {
+                return Some(StopReason::Stalled);
+            }
+        }
+        if let Some(min_diversity) = gaconfig.min_diversity {
+            if mean_hamming_distance(population) < min_diversity {
+                return Some(StopReason::DiversityCollapsed);
+            }
+        }
+        if let Some(budget) = gaconfig.time_budget {
+            if self.elapsed() >= budget {
+                return Some(StopReason::TimeBudgetExhausted);
             }
-            0.0 // Default return value in case of any failure
         }
-        // Add cases for other models here
-        _ => panic!("Model not supported"),
+        None
     }
 }
 
-pub fn run_ga<D>(dataset: Arc<D>, model: ModelName, gaconfig: GaConfig) -> Individual
+// Fails when `gaconfig` does not fit the dataset, see `GaConfig::validate`.
+pub fn run_ga<D>(dataset: Arc<D>, model: ModelName, gaconfig: GaConfig) -> Result<GaResult, String>
 where
     D: DataSet + Sync + Send, // Ensure the dataset is Sync to be shared across threads.
     f32: Sum<<D as DataSet>::Input>, // Model needs to be Cloneable and Send to be used in parallel.
 {
-    let num_features = dataset.dimension().1;
-    let population_size = gaconfig.populaton_size;
-    let generations = gaconfig.generations;
+    gaconfig.validate(dataset.dimension().1)?;
+    Ok(run_ga_on_fold(
+        dataset,
+        model,
+        gaconfig,
+        &FitnessCache::new(),
+        0,
+        None,
+        None,
+    ))
+}
+
+// Check that `checkpoint` was written by a run with the same model, data, folds and island
+// count; its cached fitness values are meaningless for any other run.
+fn check_checkpoint<D: DataSet>(
+    checkpoint: &GaCheckpoint,
+    dataset: &D,
+    model: &ModelName,
+    gaconfig: &GaConfig,
+    k_folds: Option<usize>,
+) -> Result<(), String> {
+    let (num_samples, num_features) = dataset.dimension();
+    if checkpoint.model != *model {
+        return Err(format!(
+            "checkpoint was written for {:?} but the run uses {:?}",
+            checkpoint.model, model
+        ));
+    }
+    if checkpoint.num_features != num_features {
+        return Err(format!(
+            "checkpoint has {} features but the dataset has {}",
+            checkpoint.num_features, num_features
+        ));
+    }
+    if checkpoint.num_samples != num_samples {
+        return Err(format!(
+            "checkpoint has {} samples but the dataset has {}",
+            checkpoint.num_samples, num_samples
+        ));
+    }
+    if checkpoint.k_folds != k_folds {
+        return Err(format!(
+            "checkpoint was written with {:?} folds but the run asks for {:?}",
+            checkpoint.k_folds, k_folds
+        ));
+    }
+    if checkpoint.data_fingerprint != data_fingerprint(dataset) {
+        return Err("checkpoint was written for different data".to_string());
+    }
+    if checkpoint.islands.len() != gaconfig.islands.max(1) {
+        return Err(format!(
+            "checkpoint has {} islands but the config asks for {}",
+            checkpoint.islands.len(),
+            gaconfig.islands.max(1)
+        ));
+    }
+    Ok(())
+}
+
+// Continue a run from the checkpoint at `checkpoint_path`, written by a run with the same
+// `gaconfig` and dataset. The run keeps checkpointing to `gaconfig.checkpoint_path`. A
+// checkpoint of a finished run gives back its result without running again.
+pub fn resume_ga<D>(
+    dataset: Arc<D>,
+    model: ModelName,
+    gaconfig: GaConfig,
+    checkpoint_path: &Path,
+) -> Result<GaResult, String>
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    gaconfig.validate(dataset.dimension().1)?;
+    let mut checkpoint = GaCheckpoint::load(checkpoint_path)?;
+    check_checkpoint(&checkpoint, dataset.as_ref(), &model, &gaconfig, None)?;
+    let cache = FitnessCache::from_snapshot(std::mem::take(&mut checkpoint.cache));
+    if checkpoint.is_finished() {
+        return checkpoint
+            .into_result(cache.stats())
+            .ok_or_else(|| "finished checkpoint without a result".to_string());
+    }
+    Ok(run_ga_on_fold(
+        dataset,
+        model,
+        gaconfig,
+        &cache,
+        0,
+        None,
+        Some(checkpoint),
+    ))
+}
+
+// Result of a selector outside the GA: the best member of its hall of fame (all features when
+// nothing was evaluated) together with the history and cache counters of the search.
+pub(crate) fn finish_selector(
+    hall_of_fame: HallOfFame,
+    history: Vec<GenerationStats>,
+    stop_reason: StopReason,
+    cache: &FitnessCache,
+    num_features: usize,
+) -> GaResult {
+    let best = hall_of_fame
+        .best()
+        .cloned()
+        .unwrap_or_else(|| Individual::new_all_true(num_features));
+    GaResult {
+        best,
+        hall_of_fame,
+        stop_reason,
+        generations_run: history.len(),
+        history,
+        cache_stats: cache.stats(),
+    }
+}
+
+// Draw generation 0 according to `gaconfig.initialization`, starting with the user's masks
+// when `with_initial_masks` is set, and repair every individual into the feasible region.
+pub(crate) fn initial_population(
+    gaconfig: &GaConfig,
+    population_size: usize,
+    num_features: usize,
+    with_initial_masks: bool,
+    rng: &mut GaRng,
+) -> Vec<Individual> {
+    let mut population: Vec<Individual> = vec![];
+    if with_initial_masks {
+        // lengths are checked by `GaConfig::validate`
+        for mask in gaconfig.initial_masks.iter().take(population_size) {
+            population.push(Individual::from_features(mask.clone()));
+        }
+    }
 
-    let mut population: Vec<Individual> = (0..population_size)
-        .map(|_| Individual::new(num_features))
+    if let Initialization::Mixed {
+        all_true,
+        all_false,
+    } = gaconfig.initialization
+    {
+        let all_true_count = (all_true * population_size as f64).round() as usize;
+        let all_false_count = (all_false * population_size as f64).round() as usize;
+        for _ in 0..all_true_count {
+            population.push(Individual::new_all_true(num_features));
+        }
+        for _ in 0..all_false_count {
+            population.push(Individual::from_features(Chromosome::new(num_features)));
+        }
+        population.truncate(population_size);
+    }
+
+    while population.len() < population_size {
+        let individual = match &gaconfig.initialization {
+            Initialization::Uniform | Initialization::Mixed { .. } => {
+                Individual::new(num_features, rng)
+            }
+            Initialization::Density(density) => {
+                Individual::new_with_density(num_features, *density, rng)
+            }
+            Initialization::Ranked { ranking, density } => {
+                Individual::new_ranked(num_features, ranking, *density, rng)
+            }
+        };
+        population.push(individual);
+    }
+
+    for individual in population.iter_mut() {
+        individual.repair(&gaconfig.constraints, rng);
+    }
+    population
+}
+
+// How often a duplicate is re-mutated before it is swapped for a random individual.
+const DUPLICATE_MUTATION_ATTEMPTS: usize = 5;
+
+// One sub-population of the island model, evolving with its own RNG stream.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Island {
+    population: Vec<Individual>,
+    rng: GaRng,
+    // duplicates replaced while building the current population
+    duplicates_removed: usize,
+}
+
+impl Island {
+    fn new(num_features: usize, gaconfig: &GaConfig, index: usize) -> Self {
+        // Every island draws from its own stream of the run's seed, so islands evolving on
+        // different rayon threads stay reproducible.
+        let mut rng = new_rng(gaconfig.seed);
+        rng.set_stream(index as u64);
+        // The user's masks start on the first island and reach the others by migration.
+        let population = initial_population(
+            gaconfig,
+            gaconfig.populaton_size,
+            num_features,
+            index == 0,
+            &mut rng,
+        );
+        Self {
+            population,
+            rng,
+            duplicates_removed: 0,
+        }
+    }
+
+    // Copy the elites over unchanged, then fill the rest of the generation with children of
+    // parents drawn from the best half. Expects the population sorted best first.
+    fn reproduce(&mut self, gaconfig: &GaConfig) {
+        let population_size = self.population.len();
+        let elitism_count = gaconfig.elitism_count.min(population_size);
+        let parents_count = (population_size / 2).max(1);
+        // Elites always follow raw fitness; with niching the parents follow shared fitness.
+        let parents: Vec<usize> = match gaconfig.niching {
+            Niching::None => (0..parents_count).collect(),
+            Niching::FitnessSharing { radius, alpha } => {
+                let mut order = shared_fitness_order(&self.population, radius, alpha);
+                order.truncate(parents_count);
+                order
+            }
+        };
+        let rng = &mut self.rng;
+        let mut new_generation: Vec<Individual> = self.population[..elitism_count].to_vec();
+        while new_generation.len() < population_size {
+            let first = &self.population[parents[rng.gen_range(0..parents_count)]];
+            let mut child = if rng.gen_bool(gaconfig.crossover_rate) {
+                let second = &self.population[parents[rng.gen_range(0..parents_count)]];
+                first.crossover(second, rng)
+            } else {
+                first.clone()
+            };
+            child.mutate(rng);
+            // Elites and migrants are already feasible; children are repaired before they are
+            // ever evaluated.
+            child.repair(&gaconfig.constraints, rng);
+            new_generation.push(child);
+        }
+        self.population = new_generation;
+        self.duplicates_removed = self.remove_duplicates(gaconfig);
+    }
+
+    // Replace every repeated mask after its first occurrence according to
+    // `gaconfig.duplicates`, so no mask is trained twice in a generation. Elites come first and
+    // are therefore never replaced. Returns the number of individuals replaced.
+    fn remove_duplicates(&mut self, gaconfig: &GaConfig) -> usize {
+        if gaconfig.duplicates == DuplicateHandling::Keep {
+            return 0;
+        }
+        let num_features = self
+            .population
+            .first()
+            .map_or(0, |first| first.features.len());
+        let rng = &mut self.rng;
+        let mut seen: HashSet<Chromosome> = HashSet::with_capacity(self.population.len());
+        let mut removed = 0;
+        for individual in self.population.iter_mut() {
+            if seen.insert(individual.features.clone()) {
+                continue;
+            }
+            removed += 1;
+            let mut replacement = individual.clone();
+            if let DuplicateHandling::ExtraMutation { rate } = gaconfig.duplicates {
+                for _ in 0..DUPLICATE_MUTATION_ATTEMPTS {
+                    replacement.mutate_with_rate(rate, rng);
+                    replacement.repair(&gaconfig.constraints, rng);
+                    if !seen.contains(&replacement.features) {
+                        break;
+                    }
+                }
+            }
+            // Fresh random individuals also stand in when extra mutation keeps hitting known masks.
+            if seen.contains(&replacement.features) {
+                replacement = Individual::new(num_features, rng);
+                replacement.repair(&gaconfig.constraints, rng);
+            }
+            seen.insert(replacement.features.clone());
+            *individual = replacement;
+        }
+        removed
+    }
+}
+
+// Send copies of the best `migrant_count` individuals of every island to its neighbours, where
+// they replace the worst individuals. Expects every population sorted best first and leaves
+// them sorted.
+fn migrate(islands: &mut [Island], gaconfig: &GaConfig) {
+    let num_islands = islands.len();
+    let migrant_count = gaconfig
+        .migrant_count
+        .min(gaconfig.populaton_size.saturating_sub(1));
+    if num_islands < 2 || migrant_count == 0 {
+        return;
+    }
+
+    let emigrants: Vec<Vec<Individual>> = islands
+        .iter()
+        .map(|island| island.population[..migrant_count].to_vec())
         .collect();
+    let mut arrivals: Vec<Vec<Individual>> = vec![vec![]; num_islands];
+    for (source, migrants) in emigrants.into_iter().enumerate() {
+        match gaconfig.migration_topology {
+            MigrationTopology::Ring => {
+                arrivals[(source + 1) % num_islands].extend(migrants);
+            }
+            MigrationTopology::FullyConnected => {
+                for (destination, arrival) in arrivals.iter_mut().enumerate() {
+                    if destination != source {
+                        arrival.extend(migrants.iter().cloned());
+                    }
+                }
+            }
+        }
+    }
 
-    for _ in 0..generations {
-        population.par_iter_mut().for_each(|individual| {
-            // Initialize a new model for each thread/individual.
-            // let mut model_instance = model.clone();
+    for (island, mut arrival) in islands.iter_mut().zip(arrivals) {
+        // With a fully connected topology more migrants arrive than are sent; keep the best.
+        sort_by_fitness(&mut arrival);
+        arrival.truncate(migrant_count);
+        let keep = island.population.len() - arrival.len();
+        island.population.truncate(keep);
+        island.population.extend(arrival);
+        sort_by_fitness(&mut island.population);
+    }
+}
 
-            // Dereference the Arc to get a shared reference to the dataset (D).
-            let dataset_ref = Arc::as_ref(&dataset);
+// One GA run on the training split of `fold` out of `k_folds`, sharing `cache` with the other
+// folds of the run. Starts from `resume` instead of a fresh population when given.
+fn run_ga_on_fold<D>(
+    dataset: Arc<D>,
+    model: ModelName,
+    gaconfig: GaConfig,
+    cache: &FitnessCache,
+    fold: usize,
+    k_folds: Option<usize>,
+    resume: Option<GaCheckpoint>,
+) -> GaResult
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    let (num_samples, num_features) = dataset.dimension();
+    let generations = gaconfig.generations;
+    // only checkpoints need it, and it reads the whole dataset
+    let fingerprint = gaconfig
+        .checkpoint_path
+        .as_ref()
+        .map_or(0, |_| data_fingerprint(dataset.as_ref()));
+    let mut stop_check = StopCheck::new();
+    let mut stop_reason = StopReason::MaxGenerations;
 
-            // Pass the new model instance and the dataset reference to `evaluate_fitness`.
-            individual.fitness = evaluate_fitness::<D>(individual, dataset_ref, model.clone());
-        });
+    let (mut islands, mut hall_of_fame, mut generations_run, mut history) = match resume {
+        Some(checkpoint) => {
+            for observer in &gaconfig.observers {
+                observer.on_resume(fold, checkpoint.generations_run);
+            }
+            stop_check.elapsed_before = checkpoint.elapsed;
+            stop_check.best_fitness = checkpoint.best_fitness;
+            stop_check.generations_without_improvement = checkpoint.generations_without_improvement;
+            (
+                checkpoint.islands,
+                checkpoint.hall_of_fame,
+                checkpoint.generations_run,
+                checkpoint.history,
+            )
+        }
+        None => (
+            (0..gaconfig.islands.max(1))
+                .map(|index| Island::new(num_features, &gaconfig, index))
+                .collect(),
+            // The run's answer is taken from the hall, so it always keeps at least one member.
+            HallOfFame::new(gaconfig.hall_of_fame_size.max(1)),
+            0,
+            vec![],
+        ),
+    };
 
-        // Sort the population by fitness, keep the best half, and mutate to form a new generation.
-        population.sort_by(|a, b| {
-            b.fitness
-                .partial_cmp(&a.fitness)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-        let survivors = population.split_off(population_size / 2);
-        let mut new_generation = survivors.clone();
-        new_generation.iter_mut().for_each(|individual| {
-            individual.mutate();
+    let mut evaluations = history.last().map_or(0, |stats| stats.evaluations);
+    while generations_run < generations.max(0) as usize {
+        islands.par_iter_mut().for_each(|island| {
+            island.population.par_iter_mut().for_each(|individual| {
+                // Initialize a new model for each thread/individual.
+                // let mut model_instance = model.clone();
+
+                // Dereference the Arc to get a shared reference to the dataset (D).
+                let dataset_ref = Arc::as_ref(&dataset);
+
+                // Pass the new model instance and the dataset reference to `evaluate_fitness`.
+                let negative_mse =
+                    evaluate_fitness_cached(individual, dataset_ref, model.clone(), cache, fold);
+                individual.set_score(negative_mse, gaconfig.parsimony);
+            });
+            sort_by_fitness(&mut island.population);
         });
-        population = [population, new_generation].concat();
+
+        let mut population: Vec<Individual> = islands
+            .iter()
+            .flat_map(|island| island.population.iter().cloned())
+            .collect();
+        sort_by_fitness(&mut population);
+        let previous_best = hall_of_fame
+            .best()
+            .map_or(f32::NEG_INFINITY, |best| best.fitness);
+        hall_of_fame.update(&population);
+        generations_run += 1;
+        evaluations += population.len();
+
+        if let Some(best) = hall_of_fame
+            .best()
+            .filter(|best| best.fitness > previous_best)
+        {
+            for observer in &gaconfig.observers {
+                observer.on_new_best(fold, generations_run, best);
+            }
+        }
+        let mut stats = GenerationStats::from_population(
+            fold,
+            generations_run,
+            &population,
+            stop_check.elapsed(),
+            evaluations,
+        );
+        stats.duplicates_removed = islands.iter().map(|island| island.duplicates_removed).sum();
+        let mut observer_stop = false;
+        for observer in &gaconfig.observers {
+            observer_stop |= observer.on_generation_end(&stats) == ObserverAction::Stop;
+        }
+        history.push(stats);
+        if observer_stop {
+            stop_reason = StopReason::ObserverRequested;
+            break;
+        }
+
+        if let Some(reason) = stop_check.check(&gaconfig, &population) {
+            stop_reason = reason;
+            break;
+        }
+
+        if gaconfig.migration_interval > 0 && generations_run % gaconfig.migration_interval == 0 {
+            migrate(&mut islands, &gaconfig);
+        }
+        islands
+            .par_iter_mut()
+            .for_each(|island| island.reproduce(&gaconfig));
+
+        if let Some(path) = &gaconfig.checkpoint_path {
+            if gaconfig.checkpoint_interval > 0
+                && generations_run % gaconfig.checkpoint_interval == 0
+            {
+                let checkpoint = GaCheckpoint {
+                    model: model.clone(),
+                    num_samples,
+                    num_features,
+                    data_fingerprint: fingerprint,
+                    k_folds,
+                    generations_run,
+                    islands,
+                    hall_of_fame,
+                    history,
+                    cache: cache.snapshot(),
+                    best_fitness: stop_check.best_fitness,
+                    generations_without_improvement: stop_check.generations_without_improvement,
+                    elapsed: stop_check.elapsed(),
+                    stop_reason: None,
+                };
+                // A failed save must not end an otherwise healthy run.
+                if let Err(message) = checkpoint.save(path) {
+                    eprintln!("Failed to write checkpoint {:?}: {}", path, message);
+                }
+                islands = checkpoint.islands;
+                hall_of_fame = checkpoint.hall_of_fame;
+                history = checkpoint.history;
+            }
+        }
     }
 
-    // Output the best individual's fitness and selected features.
-    let best_individual = &population[0];
-    println!("Best Fitness (Negative MSE): {}", best_individual.fitness);
-    println!("Selected Features: {:?}", best_individual.features);
-    best_individual.clone()
+    // A final checkpoint marks the run as finished, so resuming returns it without rerunning.
+    if let Some(path) = &gaconfig.checkpoint_path {
+        let checkpoint = GaCheckpoint {
+            model: model.clone(),
+            num_samples,
+            num_features,
+            data_fingerprint: fingerprint,
+            k_folds,
+            generations_run,
+            islands,
+            hall_of_fame,
+            history,
+            cache: cache.snapshot(),
+            best_fitness: stop_check.best_fitness,
+            generations_without_improvement: stop_check.generations_without_improvement,
+            elapsed: stop_check.elapsed(),
+            stop_reason: Some(stop_reason),
+        };
+        if let Err(message) = checkpoint.save(path) {
+            eprintln!("Failed to write checkpoint {:?}: {}", path, message);
+        }
+        hall_of_fame = checkpoint.hall_of_fame;
+        history = checkpoint.history;
+    }
+
+    let best_individual = hall_of_fame
+        .best()
+        .cloned()
+        .unwrap_or_else(|| Individual::new_all_true(num_features));
+    let result = GaResult {
+        best: best_individual,
+        hall_of_fame,
+        stop_reason,
+        generations_run,
+        history,
+        cache_stats: cache.stats(),
+    };
+    for observer in &gaconfig.observers {
+        observer.on_run_end(fold, &result);
+    }
+    result
 }
 
+// Run the GA on the training part of every fold, in parallel, and score its best mask on the
+// held-out part. Returns the mean held-out fitness (negative MSE, as `cross_validated_fitness`)
+// and the mask of the fold that scored highest. With a `checkpoint_path`, every fold
+// checkpoints to its own file (see `fold_checkpoint_path`).
 pub fn run_ga_cross_validation<D>(
     dataset: Arc<D>,
     model: ModelName,
     gaconfig: GaConfig,
     k_folds: usize,
-) -> (f32, Vec<bool>)
+) -> Result<(f32, Chromosome), String>
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    gaconfig.validate(dataset.dimension().1)?;
+    let folds = (0..k_folds).map(|_| None).collect();
+    Ok(cross_validate(
+        dataset,
+        model,
+        gaconfig,
+        k_folds,
+        folds,
+        FitnessCache::new(),
+    ))
+}
+
+// Continue an interrupted `run_ga_cross_validation` from the fold checkpoints next to
+// `gaconfig.checkpoint_path`. Finished folds are not run again, unfinished ones continue from
+// their last checkpoint and folds without a checkpoint start over.
+pub fn resume_ga_cross_validation<D>(
+    dataset: Arc<D>,
+    model: ModelName,
+    gaconfig: GaConfig,
+    k_folds: usize,
+) -> Result<(f32, Chromosome), String>
 where
     D: DataSet + Sync + Send,
     f32: Sum<<D as DataSet>::Input>,
 {
-    let num_samples = dataset.dimension().0;
-    let total_mse = Arc::new(Mutex::new(0.0));
-    let best_features = Arc::new(Mutex::new(vec![]));
-    let best_mse = Arc::new(Mutex::new(f32::MAX));
-
-    // Use a parallel iterator to process each fold, handling panics as errors internally.
-    (0..k_folds).into_par_iter().for_each(|fold| {
-        let dataset_clone = dataset.clone();
-        let total_mse_clone = total_mse.clone();
-        let best_features_clone = best_features.clone();
-        let best_mse_clone = best_mse.clone();
-
-        // Handle potential index out of bounds by ensuring split_for_cross_validation accounts for all data
-        let (train_set, valid_set) = dataset_clone.split_for_cross_validation(k_folds, fold);
-        println!("dc {:?}", dataset_clone.dimension());
-        println!("train set {:?}", train_set.dimension());
-        println!("valid set {:?}", valid_set.dimension());
-        // println!("valida {}", valid_set.data().data.len());
-
-        let best_individual = run_ga(Arc::new(train_set), model.clone(), gaconfig.clone());
-        println!("reach or not??");
-
-        let mse = evaluate_fitness(&best_individual, &valid_set, model.clone());
-
-        let mut total_mse_lock = total_mse_clone.lock().unwrap();
-        *total_mse_lock += mse;
-
-        let mut best_features_lock = best_features_clone.lock().unwrap();
-        let mut best_mse_lock = best_mse_clone.lock().unwrap();
-        if mse < *best_mse_lock {
-            *best_mse_lock = mse;
-            *best_features_lock = best_individual.features.clone();
+    let path = gaconfig
+        .checkpoint_path
+        .clone()
+        .ok_or("resuming a cross-validation needs gaconfig.checkpoint_path")?;
+    gaconfig.validate(dataset.dimension().1)?;
+    let cache = FitnessCache::new();
+    let mut folds = Vec::with_capacity(k_folds);
+    for fold in 0..k_folds {
+        let fold_path = fold_checkpoint_path(&path, fold);
+        if !fold_path.exists() {
+            folds.push(None);
+            continue;
         }
-    });
+        let mut checkpoint = GaCheckpoint::load(&fold_path)
+            .map_err(|message| format!("{:?}: {}", fold_path, message))?;
+        let (train_set, _) = dataset.split_for_cross_validation(k_folds, fold);
+        check_checkpoint(&checkpoint, &train_set, &model, &gaconfig, Some(k_folds))
+            .map_err(|message| format!("{:?}: {}", fold_path, message))?;
+        cache.merge(std::mem::take(&mut checkpoint.cache));
+        folds.push(Some(checkpoint));
+    }
+    Ok(cross_validate(
+        dataset, model, gaconfig, k_folds, folds, cache,
+    ))
+}
 
-    let avg_mse = {
-        let mse = total_mse.lock().unwrap();
-        *mse / k_folds as f32
-    };
+fn cross_validate<D>(
+    dataset: Arc<D>,
+    model: ModelName,
+    gaconfig: GaConfig,
+    k_folds: usize,
+    checkpoints: Vec<Option<GaCheckpoint>>,
+    cache: FitnessCache,
+) -> (f32, Chromosome)
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    let results: Vec<(f32, Individual)> = checkpoints
+        .into_par_iter()
+        .enumerate()
+        .map(|(fold, checkpoint)| {
+            let (train_set, valid_set) = dataset.split_for_cross_validation(k_folds, fold);
+            let train_set = Arc::new(train_set);
 
-    let best_features = {
-        let features = best_features.lock().unwrap();
-        features.clone()
-    };
+            // Give every fold its own stream so folds do not evolve identical populations.
+            let mut fold_config = gaconfig.clone();
+            fold_config.seed = gaconfig.seed.map(|seed| seed.wrapping_add(fold as u64));
+            fold_config.checkpoint_path = gaconfig
+                .checkpoint_path
+                .as_deref()
+                .map(|path| fold_checkpoint_path(path, fold));
+            let best_individual = match checkpoint {
+                Some(checkpoint) if checkpoint.is_finished() => {
+                    checkpoint.into_result(cache.stats())
+                }
+                checkpoint => Some(run_ga_on_fold(
+                    train_set.clone(),
+                    model.clone(),
+                    fold_config,
+                    &cache,
+                    fold,
+                    Some(k_folds),
+                    checkpoint,
+                )),
+            }
+            .map(|result| result.best)
+            .unwrap_or_else(|| Individual::new_all_true(dataset.dimension().1));
+
+            // trained on the fold's training part, scored on the held-out part
+            let fitness = evaluate_fitness_on_split(
+                &best_individual,
+                train_set.as_ref(),
+                &valid_set,
+                model.clone(),
+            );
+            for observer in &gaconfig.observers {
+                observer.on_fold_end(fold, fitness, &best_individual);
+            }
+            (fitness, best_individual)
+        })
+        .collect();
+
+    let mean_fitness = results.iter().map(|(fitness, _)| fitness).sum::<f32>() / k_folds as f32;
+    // fitness is negative MSE, so the best fold has the highest
+    let best_features = results
+        .into_iter()
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, individual)| individual.features)
+        .unwrap_or_default();
+
+    (mean_fitness, best_features)
+}
 
-    (avg_mse, best_features)
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+    use crate::{data::BreastCancerData, observer::GaObserver};
+
+    fn small_config() -> GaConfig {
+        GaConfig {
+            populaton_size: 12,
+            generations: 6,
+            crossover_rate: 0.5,
+            seed: Some(7),
+            islands: 2,
+            migration_interval: 2,
+            ..GaConfig::default()
+        }
+    }
+
+    fn run_in_pool(threads: usize, gaconfig: GaConfig) -> GaResult {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap()
+            .install(|| {
+                run_ga(
+                    Arc::new(BreastCancerData::default()),
+                    ModelName::LinearRegression,
+                    gaconfig,
+                )
+                .unwrap()
+            })
+    }
+
+    fn assert_same_run(a: &GaResult, b: &GaResult) {
+        assert_eq!(a.best.features(), b.best.features());
+        assert_eq!(a.best.fitness(), b.best.fitness());
+        assert_eq!(a.generations_run, b.generations_run);
+        let best_per_generation = |result: &GaResult| {
+            result
+                .history
+                .iter()
+                .map(|stats| stats.best_fitness)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(best_per_generation(a), best_per_generation(b));
+    }
+
+    #[test]
+    fn seeded_run_does_not_depend_on_thread_count() {
+        let single = run_in_pool(1, small_config());
+        let parallel = run_in_pool(8, small_config());
+        assert_same_run(&single, &parallel);
+    }
+
+    // Keeps a copy of the checkpoint as it was before the given generation was written.
+    struct CopyCheckpoint {
+        from: PathBuf,
+        to: PathBuf,
+        generation: usize,
+    }
+
+    impl GaObserver for CopyCheckpoint {
+        fn on_generation_end(&self, stats: &GenerationStats) -> ObserverAction {
+            if stats.generation == self.generation {
+                fs::copy(&self.from, &self.to).unwrap();
+            }
+            ObserverAction::Continue
+        }
+    }
+
+    #[test]
+    fn resumed_run_matches_uninterrupted_run() {
+        let dir = std::env::temp_dir().join(format!("fastgen-checkpoint-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("run.ckpt");
+        let interrupted = dir.join("interrupted.ckpt");
+
+        let uninterrupted = run_in_pool(1, small_config());
+        let mut gaconfig = small_config();
+        gaconfig.checkpoint_path = Some(path.clone());
+        gaconfig.checkpoint_interval = 1;
+        gaconfig.observers = vec![Arc::new(CopyCheckpoint {
+            from: path.clone(),
+            to: interrupted.clone(),
+            generation: 4,
+        })];
+        let checkpointed = run_in_pool(1, gaconfig);
+        assert_same_run(&uninterrupted, &checkpointed);
+
+        let checkpoint = GaCheckpoint::load(&interrupted).unwrap();
+        assert!(!checkpoint.is_finished());
+        assert!(checkpoint.generations_run() < uninterrupted.generations_run);
+        let resumed = resume_ga(
+            Arc::new(BreastCancerData::default()),
+            ModelName::LinearRegression,
+            small_config(),
+            &interrupted,
+        )
+        .unwrap();
+        assert_same_run(&uninterrupted, &resumed);
+
+        // the final checkpoint gives back the result without running again
+        let finished = resume_ga(
+            Arc::new(BreastCancerData::default()),
+            ModelName::LinearRegression,
+            small_config(),
+            &path,
+        )
+        .unwrap();
+        assert_same_run(&uninterrupted, &finished);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resume_rejects_a_different_model_or_dataset() {
+        let dir = std::env::temp_dir().join(format!("fastgen-mismatch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("run.ckpt");
+        let mut gaconfig = small_config();
+        gaconfig.generations = 1;
+        gaconfig.checkpoint_path = Some(path.clone());
+        let dataset = Arc::new(BreastCancerData::default());
+        run_ga(dataset.clone(), ModelName::LinearRegression, gaconfig).unwrap();
+
+        let other_model = resume_ga(
+            dataset.clone(),
+            ModelName::DecisionTreeRegressor,
+            small_config(),
+            &path,
+        );
+        assert!(other_model.is_err());
+        // same shape, rows in another order
+        let rows: Vec<usize> = (0..dataset.dimension().0).rev().collect();
+        let other_data = resume_ga(
+            Arc::new(dataset.resample(&rows)),
+            ModelName::LinearRegression,
+            small_config(),
+            &path,
+        );
+        assert!(other_data.is_err());
+        assert!(resume_ga(dataset, ModelName::LinearRegression, small_config(), &path).is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // Everything `on_fold_end` reports.
+    #[derive(Default)]
+    struct FoldScores(std::sync::Mutex<Vec<(usize, f32, Chromosome)>>);
+
+    impl GaObserver for FoldScores {
+        fn on_fold_end(&self, fold: usize, validation_fitness: f32, best: &Individual) {
+            let mut scores = self.0.lock().unwrap();
+            scores.push((fold, validation_fitness, best.features().clone()));
+        }
+    }
+
+    #[test]
+    fn cross_validation_keeps_the_fittest_fold() {
+        let dataset = Arc::new(BreastCancerData::default());
+        let scores = Arc::new(FoldScores::default());
+        let mut gaconfig = small_config();
+        gaconfig.observers = vec![scores.clone()];
+        let (mean, best) =
+            run_ga_cross_validation(dataset.clone(), ModelName::LinearRegression, gaconfig, 3)
+                .unwrap();
+
+        let scores = scores.0.lock().unwrap();
+        assert_eq!(scores.len(), 3);
+        for (fold, fitness, mask) in scores.iter() {
+            let (train, valid) = dataset.split_for_cross_validation(3, *fold);
+            let held_out = evaluate_fitness_on_split(
+                &Individual::from_features(mask.clone()),
+                &train,
+                &valid,
+                ModelName::LinearRegression,
+            );
+            assert_eq!(*fitness, held_out);
+        }
+        let total: f32 = scores.iter().map(|(_, fitness, _)| fitness).sum();
+        assert!((mean - total / 3.0).abs() < 1e-6);
+        let fittest = scores.iter().max_by(|a, b| a.1.total_cmp(&b.1)).unwrap();
+        assert_eq!(best, fittest.2);
+    }
+
+    #[test]
+    fn config_that_does_not_fit_the_dataset_is_an_error() {
+        let dataset = Arc::new(BreastCancerData::default());
+        let mut gaconfig = small_config();
+        gaconfig.initial_masks = vec![Chromosome::new_all_true(5)];
+        assert!(run_ga(dataset.clone(), ModelName::LinearRegression, gaconfig).is_err());
+
+        let mut gaconfig = small_config();
+        gaconfig.constraints.required = vec![3];
+        gaconfig.constraints.excluded = vec![3];
+        assert!(
+            run_ga_cross_validation(dataset, ModelName::LinearRegression, gaconfig, 2).is_err()
+        );
+    }
 }
@@ -0,0 +1,132 @@
+use rand::seq::SliceRandom;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use smartcore::{
+    ensemble::random_forest_regressor::RandomForestRegressor,
+    error::Failed,
+    linalg::{naive::dense_matrix::DenseMatrix, BaseMatrix},
+    linear::linear_regression::LinearRegression,
+    math::num::RealNumber,
+};
+
+use crate::{chromosome::Chromosome, data::DataSet, ga::new_rng, model::ModelName};
+
+// A model of `ModelName` fitted once and used for many predictions.
+enum FittedModel<T: RealNumber> {
+    Linear(LinearRegression<T, DenseMatrix<T>>),
+    Forest(RandomForestRegressor<T>),
+}
+
+impl<T: RealNumber> FittedModel<T> {
+    fn fit(x: &DenseMatrix<T>, y: &Vec<T>, model: &ModelName) -> Result<Self, Failed> {
+        Ok(match model {
+            ModelName::LinearRegression => {
+                FittedModel::Linear(LinearRegression::fit(x, y, Default::default())?)
+            }
+            ModelName::DecisionTreeRegressor => {
+                FittedModel::Forest(RandomForestRegressor::fit(x, y, Default::default())?)
+            }
+        })
+    }
+
+    fn mse(&self, x: &DenseMatrix<T>, y: &[T]) -> f32 {
+        let predictions = match self {
+            FittedModel::Linear(lr) => lr.predict(x),
+            FittedModel::Forest(forest) => forest.predict(x),
+        };
+        match predictions {
+            Ok(predictions) => mean_squared_error(y, &predictions),
+            Err(_) => f32::INFINITY,
+        }
+    }
+}
+
+fn mean_squared_error<T: RealNumber>(actual: &[T], predicted: &[T]) -> f32 {
+    let sum: f64 = actual
+        .iter()
+        .zip(predicted.iter())
+        .map(|(&a, &p)| ((a - p) * (a - p)).to_f64().unwrap_or(f64::INFINITY))
+        .sum();
+    (sum / actual.len().max(1) as f64) as f32
+}
+
+fn column_std<T: RealNumber>(x: &DenseMatrix<T>, col: usize) -> f32 {
+    let values: Vec<f64> = x
+        .get_col_as_vec(col)
+        .iter()
+        .map(|v| v.to_f64().unwrap_or(0.0))
+        .collect();
+    let mean = values.iter().sum::<f64>() / values.len().max(1) as f64;
+    let variance =
+        values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / values.len().max(1) as f64;
+    variance.sqrt() as f32
+}
+
+// Increase in training MSE when one column of `x` is shuffled, for every column. The model is
+// fitted once; each column is shuffled with its own RNG stream of `seed`, so the result does not
+// depend on how rayon schedules the columns.
+pub fn permutation_importances_matrix<T: RealNumber + Send + Sync>(
+    x: &DenseMatrix<T>,
+    y: &Vec<T>,
+    model: &ModelName,
+    seed: Option<u64>,
+) -> Vec<f32> {
+    let (rows, cols) = x.shape();
+    let fitted = match FittedModel::fit(x, y, model) {
+        Ok(fitted) => fitted,
+        Err(_) => return vec![0.0; cols],
+    };
+    let baseline = fitted.mse(x, y);
+    (0..cols)
+        .into_par_iter()
+        .map(|col| {
+            let mut rng = new_rng(seed);
+            rng.set_stream(col as u64);
+            let mut order: Vec<usize> = (0..rows).collect();
+            order.shuffle(&mut rng);
+            let column = x.get_col_as_vec(col);
+            let mut shuffled = x.clone();
+            for (row, &source) in order.iter().enumerate() {
+                shuffled.set(row, col, column[source]);
+            }
+            fitted.mse(&shuffled, y) - baseline
+        })
+        .collect()
+}
+
+// Absolute coefficients of a linear regression on `x`, scaled by each column's standard
+// deviation so features on different scales compare fairly.
+pub fn coefficient_importances_matrix<T: RealNumber>(x: &DenseMatrix<T>, y: &Vec<T>) -> Vec<f32> {
+    let cols = x.shape().1;
+    match LinearRegression::fit(x, y, Default::default()) {
+        Ok(lr) => (0..cols)
+            .map(|col| {
+                let coefficient = lr.coefficients().get(col, 0).to_f32().unwrap_or(0.0);
+                coefficient.abs() * column_std(x, col)
+            })
+            .collect(),
+        Err(_) => vec![0.0; cols],
+    }
+}
+
+// Importance of every selected feature of `features` for `model`, in `features.iter_ones()`
+// order: standardised coefficient size for `LinearRegression`, permutation importance for the
+// random forest behind `DecisionTreeRegressor`.
+pub fn feature_importances<D>(
+    dataset: &D,
+    features: &Chromosome,
+    model: &ModelName,
+    seed: Option<u64>,
+) -> Vec<f32>
+where
+    D: DataSet,
+    D::Input: Send + Sync,
+{
+    let Some(x) = dataset.select_columns(features) else {
+        return vec![];
+    };
+    let y = dataset.target();
+    match model {
+        ModelName::LinearRegression => coefficient_importances_matrix(&x, &y),
+        ModelName::DecisionTreeRegressor => permutation_importances_matrix(&x, &y, model, seed),
+    }
+}
@@ -0,0 +1,312 @@
+use rayon::prelude::*;
+
+use crate::{
+    chromosome::Chromosome,
+    config::{L1PathConfig, L1Penalty},
+    data::DataSet,
+    filter::{columns, target},
+};
+
+// One regularisation strength of the path.
+#[derive(Clone, Debug)]
+pub struct L1PathPoint {
+    pub alpha: f64,
+    // fitted on the whole dataset, one per feature, in the units of the original columns
+    pub coefficients: Vec<f64>,
+    // features with a non-zero coefficient
+    pub features: Chromosome,
+    // mean validation MSE over the folds
+    pub cv_error: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct L1PathResult {
+    // strongest regularisation first
+    pub path: Vec<L1PathPoint>,
+    pub best_alpha: f64,
+    // non-zero features at `best_alpha`
+    pub best: Chromosome,
+    pub best_error: f32,
+}
+
+// Training data with zero-mean, unit-variance columns and a centred target.
+struct Standardized {
+    columns: Vec<Vec<f64>>,
+    means: Vec<f64>,
+    // 0.0 for constant columns, which never enter the model
+    stds: Vec<f64>,
+    target: Vec<f64>,
+    target_mean: f64,
+}
+
+impl Standardized {
+    fn new(columns: Vec<Vec<f64>>, target: Vec<f64>) -> Self {
+        let n = target.len().max(1) as f64;
+        let target_mean = target.iter().sum::<f64>() / n;
+        let (mut means, mut stds) = (vec![], vec![]);
+        let columns = columns
+            .into_iter()
+            .map(|column| {
+                let mean = column.iter().sum::<f64>() / n;
+                let std = (column.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n).sqrt();
+                means.push(mean);
+                stds.push(std);
+                if std == 0.0 {
+                    return vec![0.0; column.len()];
+                }
+                column.iter().map(|x| (x - mean) / std).collect()
+            })
+            .collect();
+        Self {
+            columns,
+            means,
+            stds,
+            target: target.iter().map(|y| y - target_mean).collect(),
+            target_mean,
+        }
+    }
+
+    // Smallest alpha at which every coefficient is zero.
+    fn alpha_max(&self, l1_ratio: f64) -> f64 {
+        let n = self.target.len().max(1) as f64;
+        self.columns
+            .iter()
+            .map(|column| dot(column, &self.target).abs() / n)
+            .fold(0.0, f64::max)
+            / l1_ratio
+    }
+
+    // Coordinate descent from the warm start `weights` (standardised scale).
+    fn fit(&self, alpha: f64, l1_ratio: f64, config: &L1PathConfig, weights: &mut [f64]) {
+        let n = self.target.len().max(1) as f64;
+        let l1 = alpha * l1_ratio;
+        let l2 = alpha * (1.0 - l1_ratio);
+        let mut residual = self.target.clone();
+        for (column, &w) in self.columns.iter().zip(weights.iter()) {
+            if w != 0.0 {
+                for (r, x) in residual.iter_mut().zip(column) {
+                    *r -= w * x;
+                }
+            }
+        }
+        for _ in 0..config.max_iterations {
+            let mut max_change: f64 = 0.0;
+            for (j, column) in self.columns.iter().enumerate() {
+                if self.stds[j] == 0.0 {
+                    continue;
+                }
+                let old = weights[j];
+                // columns have unit variance, so x_j . x_j / n = 1
+                let rho = dot(column, &residual) / n + old;
+                let new = soft_threshold(rho, l1) / (1.0 + l2);
+                if new != old {
+                    for (r, x) in residual.iter_mut().zip(column) {
+                        *r -= (new - old) * x;
+                    }
+                    weights[j] = new;
+                    max_change = max_change.max((new - old).abs());
+                }
+            }
+            if max_change < config.tolerance {
+                break;
+            }
+        }
+    }
+
+    // Coefficients in the units of the original columns, and the intercept.
+    fn unscaled(&self, weights: &[f64]) -> (Vec<f64>, f64) {
+        let coefficients: Vec<f64> = weights
+            .iter()
+            .zip(&self.stds)
+            .map(|(w, std)| if *std == 0.0 { 0.0 } else { w / std })
+            .collect();
+        let intercept = self.target_mean
+            - coefficients
+                .iter()
+                .zip(&self.means)
+                .map(|(w, mean)| w * mean)
+                .sum::<f64>();
+        (coefficients, intercept)
+    }
+
+    // Weights for every alpha of `alphas` (descending), each fit warm-started from the last.
+    fn path(&self, alphas: &[f64], l1_ratio: f64, config: &L1PathConfig) -> Vec<Vec<f64>> {
+        let mut weights = vec![0.0; self.columns.len()];
+        alphas
+            .iter()
+            .map(|&alpha| {
+                self.fit(alpha, l1_ratio, config, &mut weights);
+                weights.clone()
+            })
+            .collect()
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn soft_threshold(x: f64, threshold: f64) -> f64 {
+    if x > threshold {
+        x - threshold
+    } else if x < -threshold {
+        x + threshold
+    } else {
+        0.0
+    }
+}
+
+// Validation MSE of the model given by `weights` of `train` on the held-out columns.
+fn validation_error(train: &Standardized, weights: &[f64], columns: &[Vec<f64>], y: &[f64]) -> f32 {
+    let (coefficients, intercept) = train.unscaled(weights);
+    let squared_errors: f64 = y
+        .iter()
+        .enumerate()
+        .map(|(i, actual)| {
+            let predicted = intercept
+                + coefficients
+                    .iter()
+                    .zip(columns)
+                    .map(|(w, column)| w * column[i])
+                    .sum::<f64>();
+            (actual - predicted) * (actual - predicted)
+        })
+        .sum();
+    (squared_errors / y.len().max(1) as f64) as f32
+}
+
+// Embedded selection by L1 regularisation: fit Lasso (or ElasticNet) by coordinate descent
+// along a grid of alphas, strongest first with warm starts, record which coefficients stay
+// non-zero, and score every alpha by k-fold cross-validation. The folds run in parallel.
+// `best` is the feature set at the alpha with the lowest validation MSE; ties go to the
+// stronger regularisation.
+pub fn run_l1_path<D>(dataset: &D, config: &L1PathConfig) -> Result<L1PathResult, String>
+where
+    D: DataSet + Sync,
+{
+    let l1_ratio = match config.penalty {
+        L1Penalty::Lasso => 1.0,
+        L1Penalty::ElasticNet { l1_ratio } if l1_ratio > 0.0 && l1_ratio <= 1.0 => l1_ratio,
+        L1Penalty::ElasticNet { l1_ratio } => {
+            return Err(format!("l1_ratio must be in (0, 1], got {}", l1_ratio))
+        }
+    };
+    let full = Standardized::new(columns(dataset), target(dataset));
+    if full.columns.is_empty() {
+        return Err("dataset has no features".to_string());
+    }
+
+    let mut alphas = config.alphas.clone();
+    if alphas.is_empty() {
+        let max = full.alpha_max(l1_ratio);
+        let steps = config.num_alphas.max(2);
+        alphas = (0..steps)
+            .map(|i| max * 1e-3f64.powf(i as f64 / (steps - 1) as f64))
+            .collect();
+    }
+    alphas.sort_by(|a, b| b.total_cmp(a));
+
+    let k_folds = config.k_folds.max(2);
+    let fold_errors: Vec<Vec<f32>> = (0..k_folds)
+        .into_par_iter()
+        .map(|fold| {
+            let (train, valid) = dataset.split_for_cross_validation(k_folds, fold);
+            let train = Standardized::new(columns(&train), target(&train));
+            let (valid_columns, valid_target) = (columns(&valid), target(&valid));
+            train
+                .path(&alphas, l1_ratio, config)
+                .iter()
+                .map(|weights| validation_error(&train, weights, &valid_columns, &valid_target))
+                .collect()
+        })
+        .collect();
+
+    let path: Vec<L1PathPoint> = full
+        .path(&alphas, l1_ratio, config)
+        .iter()
+        .zip(&alphas)
+        .enumerate()
+        .map(|(i, (weights, &alpha))| {
+            let (coefficients, _) = full.unscaled(weights);
+            let features = Chromosome::from(
+                coefficients
+                    .iter()
+                    .map(|w| *w != 0.0)
+                    .collect::<Vec<bool>>(),
+            );
+            let cv_error = fold_errors.iter().map(|errors| errors[i]).sum::<f32>() / k_folds as f32;
+            L1PathPoint {
+                alpha,
+                coefficients,
+                features,
+                cv_error,
+            }
+        })
+        .collect();
+
+    let best = path
+        .iter()
+        .fold(None::<&L1PathPoint>, |best, point| match best {
+            Some(best) if best.cv_error <= point.cv_error => Some(best),
+            _ => Some(point),
+        })
+        .ok_or("no regularisation strength to fit")?
+        .clone();
+    Ok(L1PathResult {
+        best_alpha: best.alpha,
+        best: best.features,
+        best_error: best.cv_error,
+        path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // y = 1 + 2 x0 - 3 x1, with x2 irrelevant and the columns far from collinear
+    fn linear() -> Standardized {
+        let x0: Vec<f64> = (0..20).map(|i| i as f64).collect();
+        let x1: Vec<f64> = (0..20).map(|i| ((i * 7) % 11) as f64).collect();
+        let x2: Vec<f64> = (0..20).map(|i| ((i * 3) % 5) as f64 - 2.0).collect();
+        let y = x0
+            .iter()
+            .zip(&x1)
+            .map(|(a, b)| 1.0 + 2.0 * a - 3.0 * b)
+            .collect();
+        Standardized::new(vec![x0, x1, x2], y)
+    }
+
+    fn config() -> L1PathConfig {
+        L1PathConfig {
+            tolerance: 1e-12,
+            max_iterations: 100_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn alpha_max_zeroes_every_coefficient() {
+        let data = linear();
+        for l1_ratio in [1.0, 0.5] {
+            let alpha_max = data.alpha_max(l1_ratio);
+            let mut weights = vec![0.0; 3];
+            data.fit(alpha_max, l1_ratio, &config(), &mut weights);
+            assert!(weights.iter().all(|&w| w == 0.0), "{:?}", weights);
+            data.fit(alpha_max * 0.99, l1_ratio, &config(), &mut weights);
+            assert!(weights.iter().any(|&w| w != 0.0));
+        }
+    }
+
+    #[test]
+    fn tiny_alpha_is_close_to_least_squares() {
+        let data = linear();
+        let mut weights = vec![0.0; 3];
+        data.fit(1e-9, 1.0, &config(), &mut weights);
+        let (coefficients, intercept) = data.unscaled(&weights);
+        for (fitted, exact) in coefficients.iter().zip([2.0, -3.0, 0.0]) {
+            assert!((fitted - exact).abs() < 1e-6, "{:?}", coefficients);
+        }
+        assert!((intercept - 1.0).abs() < 1e-5, "{}", intercept);
+    }
+}
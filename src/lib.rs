@@ -1,5 +1,22 @@
+pub mod annealing;
+pub mod boruta;
+pub mod cache;
+pub mod checkpoint;
+pub mod chromosome;
 pub mod config;
+pub mod constraints;
 pub mod data;
+pub mod diversity;
+pub mod exhaustive;
+pub mod filter;
 pub mod ga;
+pub mod importance;
+pub mod lasso;
 pub mod model;
+pub mod nsga;
+pub mod observer;
+pub mod pso;
+pub mod rfe;
+pub mod sequential;
+pub mod stability;
 pub mod viz;
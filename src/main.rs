@@ -1,8 +1,9 @@
 use fastgen::{
-    config::GaConfig,
+    config::{GaConfig, L1PathConfig},
     data::{BreastCancerData, DataSet},
-    ga::{evaluate_fitness, run_ga, run_ga_cross_validation, Individual},
-    model::{self, LinearRegressionModel, Model, ModelName},
+    ga::{cross_validated_fitness, run_ga_cross_validation, Individual},
+    lasso::run_l1_path,
+    model::ModelName,
 };
 use std::{sync::Arc, time::Instant};
 
@@ -65,6 +66,7 @@ fn main() {
         generations: 100,
         // population_size: 50,
         populaton_size: 50,
+        ..Default::default()
     };
 
     // Define the number of folds for cross-validation
@@ -76,22 +78,41 @@ fn main() {
         ModelName::DecisionTreeRegressor, // Make sure this matches your ModelName enum
     ];
 
+    // Embedded L1 baseline: the CV-optimal Lasso feature set, scored below with every model
+    let l1_start_time = Instant::now();
+    let l1_path =
+        run_l1_path(data_arc.as_ref(), &L1PathConfig::default()).expect("Lasso path failed");
+    let l1_time = l1_start_time.elapsed();
+    let l1_individual = Individual::from_features(l1_path.best.clone());
+
     for model in models {
         // Time the GA for feature selection
         let ga_start_time = Instant::now();
-        let (ga_mse, best_features) =
-            run_ga_cross_validation(data_arc.clone(), model.clone(), ga_config.clone(), k_folds);
+        // every fold's best mask is scored on that fold's held-out rows
+        let (ga_fitness, best_features) =
+            run_ga_cross_validation(data_arc.clone(), model.clone(), ga_config.clone(), k_folds)
+                .expect("GA cross-validation failed");
         let ga_time = ga_start_time.elapsed();
-        let num_features_selected_by_ga = best_features.iter().filter(|&&f| f).count(); // Count the features selected by GA
+        let num_features_selected_by_ga = best_features.count_ones(); // Count the features selected by GA
 
-        // Use the first fold as a proxy for validation to get baseline MSE
+        // Baseline and L1 are scored on the same folds, so all three numbers compare
         let num_features = data_arc.dimension().1;
         let individual = Individual::new_all_true(num_features); // All features are true for the baseline
-        let (train_set, valid_set) = data_arc.split_for_cross_validation(k_folds, 0);
-        let baseline_mse = evaluate_fitness(&individual, &valid_set, model.clone());
+        let baseline_fitness =
+            cross_validated_fitness(&individual, data_arc.as_ref(), model.clone(), k_folds);
+        let l1_fitness =
+            cross_validated_fitness(&l1_individual, data_arc.as_ref(), model.clone(), k_folds);
 
-        // Print out GA time, GA MSE, Baseline MSE, and number of features
-        println!("Model: {:?}, GA Time: {:?}, GA MSE: {}, Baseline MSE: {}, Features (Baseline/GA): {}/{}",
-                 model, ga_time, ga_mse, baseline_mse, num_features, num_features_selected_by_ga);
+        // Print out GA time, held-out fitness (negative MSE) of GA and baseline, and number of features
+        println!("Model: {:?}, GA Time: {:?}, GA CV fitness: {}, Baseline CV fitness: {}, Features (Baseline/GA): {}/{}",
+                 model, ga_time, ga_fitness, baseline_fitness, num_features, num_features_selected_by_ga);
+        println!(
+            "Model: {:?}, L1 Time: {:?}, L1 CV fitness: {}, Features (L1): {} at alpha {}",
+            model,
+            l1_time,
+            l1_fitness,
+            l1_path.best.count_ones(),
+            l1_path.best_alpha
+        );
     }
 }
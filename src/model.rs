@@ -1,10 +1,9 @@
+use serde::{Deserialize, Serialize};
 use smartcore::{
     linalg::naive::dense_matrix::DenseMatrix, linear::linear_regression::LinearRegression,
-    math::num::RealNumber, metrics::mean_squared_error,
-    tree::decision_tree_regressor::DecisionTreeRegressor,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ModelName {
     LinearRegression,
     DecisionTreeRegressor,
@@ -23,10 +22,12 @@ pub trait Model<D: DataSet> {
     // fn evaluate(actual: &[D::Input], predicted: &[D::Output]) -> Result<f32, String>;
 }
 
+#[derive(Default)]
 pub struct LinearRegressionModel
 // where
 //     D: DataSet<Input = f64, Output = f64>, // Constraint for simplicity
 {
+    #[allow(dead_code)]
     model: Option<LinearRegression<f32, DenseMatrix<f32>>>, // Storing the model
 }
 
@@ -36,14 +37,6 @@ impl Clone for LinearRegressionModel {
     }
 }
 
-impl Default for LinearRegressionModel {
-    fn default() -> Self {
-        Self {
-            model: Default::default(),
-        }
-    }
-}
-
 impl<D> Model<D> for LinearRegressionModel
 where
     D: DataSet<Input = f32, Output = f32>, // Ensure D::Input and D::Output are f64
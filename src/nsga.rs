@@ -0,0 +1,315 @@
+use std::{cmp::Ordering, collections::HashSet, iter::Sum, sync::Arc};
+
+use rand::Rng;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::{
+    cache::FitnessCache,
+    chromosome::Chromosome,
+    config::GaConfig,
+    data::DataSet,
+    ga::{evaluate_fitness_cached, initial_population, new_rng, GaRng, Individual},
+    model::ModelName,
+};
+
+type ObjectiveFn<D> = dyn Fn(&Chromosome, &D) -> f32 + Send + Sync;
+
+// Tries per generation, as a multiple of the population size, at producing offspring whose mask
+// is not already in the population; a search space this crowded yields fewer offspring.
+const OFFSPRING_ATTEMPTS: usize = 10;
+
+// A quantity to minimise for a feature mask on a dataset.
+pub struct Objective<D> {
+    pub name: String,
+    evaluate: Box<ObjectiveFn<D>>,
+}
+
+impl<D: DataSet> Objective<D> {
+    pub fn new<F>(name: &str, evaluate: F) -> Self
+    where
+        F: Fn(&Chromosome, &D) -> f32 + Send + Sync + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            evaluate: Box::new(evaluate),
+        }
+    }
+
+    // MSE of `model` trained on the selected features; an empty mask or a failed fit has
+    // infinite error. Masks seen before are looked up in the objective's own fitness cache.
+    pub fn error(model: ModelName) -> Self
+    where
+        f32: Sum<<D as DataSet>::Input>,
+    {
+        let cache = FitnessCache::new();
+        Self::new("mse", move |features, dataset| {
+            let individual = Individual::from_features(features.clone());
+            -evaluate_fitness_cached(&individual, dataset, model.clone(), &cache, 0)
+        })
+    }
+
+    // number of selected features
+    pub fn feature_count() -> Self {
+        Self::new("feature_count", |features, _| features.count_ones() as f32)
+    }
+
+    pub fn evaluate(&self, features: &Chromosome, dataset: &D) -> f32 {
+        (self.evaluate)(features, dataset)
+    }
+}
+
+// One non-dominated feature set and its objective values, in the order the objectives were given.
+#[derive(Clone, Debug)]
+pub struct ParetoSolution {
+    pub features: Chromosome,
+    pub objectives: Vec<f32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ParetoFront {
+    pub objective_names: Vec<String>,
+    // distinct masks of the first front, sorted by the first objective
+    pub solutions: Vec<ParetoSolution>,
+}
+
+#[derive(Clone)]
+struct Candidate {
+    individual: Individual,
+    objectives: Vec<f32>,
+    rank: usize,
+    crowding_distance: f32,
+}
+
+// `a` dominates `b` when it is no worse on every objective and strictly better on one.
+fn dominates(a: &[f32], b: &[f32]) -> bool {
+    let mut strictly_better = false;
+    for (x, y) in a.iter().zip(b.iter()) {
+        if x > y {
+            return false;
+        }
+        if x < y {
+            strictly_better = true;
+        }
+    }
+    strictly_better
+}
+
+// Fast non-dominated sort: sets every candidate's rank and returns the fronts, best first.
+fn non_dominated_sort(candidates: &mut [Candidate]) -> Vec<Vec<usize>> {
+    let n = candidates.len();
+    let mut dominated_by: Vec<Vec<usize>> = vec![vec![]; n];
+    let mut domination_count = vec![0; n];
+    let mut fronts = vec![vec![]];
+
+    for p in 0..n {
+        for q in 0..n {
+            if dominates(&candidates[p].objectives, &candidates[q].objectives) {
+                dominated_by[p].push(q);
+            } else if dominates(&candidates[q].objectives, &candidates[p].objectives) {
+                domination_count[p] += 1;
+            }
+        }
+        if domination_count[p] == 0 {
+            candidates[p].rank = 0;
+            fronts[0].push(p);
+        }
+    }
+
+    let mut current = 0;
+    while !fronts[current].is_empty() {
+        let mut next = vec![];
+        for &p in &fronts[current] {
+            for &q in &dominated_by[p] {
+                domination_count[q] -= 1;
+                if domination_count[q] == 0 {
+                    candidates[q].rank = current + 1;
+                    next.push(q);
+                }
+            }
+        }
+        current += 1;
+        fronts.push(next);
+    }
+    fronts.pop();
+    fronts
+}
+
+// Crowding distance of every candidate in `front`; boundary points get infinity so they are kept.
+fn assign_crowding_distance(candidates: &mut [Candidate], front: &[usize]) {
+    for &i in front {
+        candidates[i].crowding_distance = 0.0;
+    }
+    let num_objectives = candidates[front[0]].objectives.len();
+    let mut order = front.to_vec();
+    for m in 0..num_objectives {
+        order.sort_by(|&a, &b| {
+            candidates[a].objectives[m]
+                .partial_cmp(&candidates[b].objectives[m])
+                .unwrap_or(Ordering::Equal)
+        });
+        let min = candidates[order[0]].objectives[m];
+        let max = candidates[order[order.len() - 1]].objectives[m];
+        candidates[order[0]].crowding_distance = f32::INFINITY;
+        candidates[order[order.len() - 1]].crowding_distance = f32::INFINITY;
+        if max - min <= f32::EPSILON || !(max - min).is_finite() {
+            continue;
+        }
+        for w in 1..order.len().saturating_sub(1) {
+            let gap =
+                candidates[order[w + 1]].objectives[m] - candidates[order[w - 1]].objectives[m];
+            candidates[order[w]].crowding_distance += gap / (max - min);
+        }
+    }
+}
+
+// Lower rank wins, then the less crowded candidate.
+fn crowded_compare(a: &Candidate, b: &Candidate) -> Ordering {
+    a.rank.cmp(&b.rank).then_with(|| {
+        b.crowding_distance
+            .partial_cmp(&a.crowding_distance)
+            .unwrap_or(Ordering::Equal)
+    })
+}
+
+fn binary_tournament<'a>(population: &'a [Candidate], rng: &mut GaRng) -> &'a Candidate {
+    let a = &population[rng.gen_range(0..population.len())];
+    let b = &population[rng.gen_range(0..population.len())];
+    if crowded_compare(a, b) == Ordering::Greater {
+        b
+    } else {
+        a
+    }
+}
+
+fn evaluate_all<D>(
+    individuals: Vec<Individual>,
+    dataset: &D,
+    objectives: &[Objective<D>],
+) -> Vec<Candidate>
+where
+    D: DataSet + Sync + Send,
+{
+    individuals
+        .par_iter()
+        .map(|individual| Candidate {
+            objectives: objectives
+                .iter()
+                .map(|objective| objective.evaluate(individual.features(), dataset))
+                .collect(),
+            individual: individual.clone(),
+            rank: 0,
+            crowding_distance: 0.0,
+        })
+        .collect()
+}
+
+// NSGA-II over feature masks minimising (MSE, number of selected features).
+pub fn run_nsga2<D>(
+    dataset: Arc<D>,
+    model: ModelName,
+    gaconfig: GaConfig,
+) -> Result<ParetoFront, String>
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    let objectives = vec![Objective::error(model), Objective::feature_count()];
+    run_nsga2_with_objectives(dataset, objectives, gaconfig)
+}
+
+// NSGA-II over feature masks minimising every objective in `objectives`. Uses the population
+// size, generation count, crossover rate and seed of `gaconfig`; `GaConfig::nsga2` has defaults
+// suited to it. Offspring that repeat a mask already in the population are dropped.
+pub fn run_nsga2_with_objectives<D>(
+    dataset: Arc<D>,
+    objectives: Vec<Objective<D>>,
+    gaconfig: GaConfig,
+) -> Result<ParetoFront, String>
+where
+    D: DataSet + Sync + Send,
+{
+    if objectives.is_empty() {
+        return Err("NSGA-II needs at least one objective".to_string());
+    }
+    let num_features = dataset.dimension().1;
+    gaconfig.validate(num_features)?;
+    let population_size = gaconfig.populaton_size.max(2);
+    let mut rng = new_rng(gaconfig.seed);
+
+    let initial = initial_population(&gaconfig, population_size, num_features, true, &mut rng);
+    let mut population = evaluate_all(initial, dataset.as_ref(), &objectives);
+    for front in non_dominated_sort(&mut population) {
+        assign_crowding_distance(&mut population, &front);
+    }
+
+    for _ in 0..gaconfig.generations {
+        let mut offspring = Vec::with_capacity(population_size);
+        let mut seen: HashSet<Chromosome> = population
+            .iter()
+            .map(|candidate| candidate.individual.features().clone())
+            .collect();
+        let mut attempts = 0;
+        while offspring.len() < population_size && attempts < population_size * OFFSPRING_ATTEMPTS {
+            attempts += 1;
+            let first = &binary_tournament(&population, &mut rng).individual;
+            let mut child = if rng.gen_bool(gaconfig.crossover_rate) {
+                let second = &binary_tournament(&population, &mut rng).individual;
+                first.crossover(second, &mut rng)
+            } else {
+                first.clone()
+            };
+            child.mutate(&mut rng);
+            child.repair(&gaconfig.constraints, &mut rng);
+            if seen.insert(child.features().clone()) {
+                offspring.push(child);
+            }
+        }
+
+        // Parents keep their objective values; only the offspring are trained.
+        let mut combined = population;
+        combined.extend(evaluate_all(offspring, dataset.as_ref(), &objectives));
+
+        let mut next = Vec::with_capacity(population_size);
+        for front in non_dominated_sort(&mut combined) {
+            assign_crowding_distance(&mut combined, &front);
+            if next.len() + front.len() <= population_size {
+                next.extend(front.iter().map(|&i| combined[i].clone()));
+            } else {
+                let mut last: Vec<Candidate> = front.iter().map(|&i| combined[i].clone()).collect();
+                last.sort_by(crowded_compare);
+                last.truncate(population_size - next.len());
+                next.extend(last);
+            }
+            if next.len() == population_size {
+                break;
+            }
+        }
+        population = next;
+    }
+
+    let fronts = non_dominated_sort(&mut population);
+    let mut solutions: Vec<ParetoSolution> = vec![];
+    for &i in &fronts[0] {
+        let candidate = &population[i];
+        if solutions
+            .iter()
+            .any(|solution| &solution.features == candidate.individual.features())
+        {
+            continue;
+        }
+        solutions.push(ParetoSolution {
+            features: candidate.individual.features().clone(),
+            objectives: candidate.objectives.clone(),
+        });
+    }
+    solutions.sort_by(|a, b| {
+        a.objectives[0]
+            .partial_cmp(&b.objectives[0])
+            .unwrap_or(Ordering::Equal)
+    });
+
+    Ok(ParetoFront {
+        objective_names: objectives.iter().map(|o| o.name.clone()).collect(),
+        solutions,
+    })
+}
@@ -0,0 +1,206 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    diversity::DiversityMetrics,
+    ga::{GaResult, Individual},
+};
+
+// Summary of one evaluated generation, handed to every observer and kept in `GaResult::history`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GenerationStats {
+    // cross-validation fold of the run; 0 outside cross-validation
+    pub fold: usize,
+    // 1 for the first evaluated generation
+    pub generation: usize,
+    // fitness statistics over the individuals that could be scored; a mask without features
+    // has fitness -inf and is left out. NaN when no individual could be scored.
+    pub best_fitness: f32,
+    pub mean_fitness: f32,
+    pub worst_fitness: f32,
+    pub std_fitness: f32,
+    pub mean_selected_features: f32,
+    pub diversity: DiversityMetrics,
+    // seconds since the run started
+    pub elapsed_secs: f64,
+    // individuals scored so far in this run, cached or not
+    pub evaluations: usize,
+    // duplicate masks replaced while this generation was built
+    pub duplicates_removed: usize,
+}
+
+impl GenerationStats {
+    pub(crate) fn from_population(
+        fold: usize,
+        generation: usize,
+        population: &[Individual],
+        elapsed: Duration,
+        evaluations: usize,
+    ) -> Self {
+        let fitness: Vec<f32> = population
+            .iter()
+            .map(|individual| individual.fitness())
+            .filter(|fitness| fitness.is_finite())
+            .collect();
+        let count = fitness.len() as f32;
+        let mean_fitness = fitness.iter().sum::<f32>() / count;
+        let variance = fitness
+            .iter()
+            .map(|f| (f - mean_fitness) * (f - mean_fitness))
+            .sum::<f32>()
+            / count;
+        // folding nothing would report -inf and +inf as if they had been scored
+        let (best_fitness, worst_fitness) = if fitness.is_empty() {
+            (f32::NAN, f32::NAN)
+        } else {
+            (
+                fitness.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+                fitness.iter().copied().fold(f32::INFINITY, f32::min),
+            )
+        };
+        let mean_selected_features = population
+            .iter()
+            .map(|individual| individual.features().count_ones())
+            .sum::<usize>() as f32
+            / population.len().max(1) as f32;
+
+        Self {
+            fold,
+            generation,
+            best_fitness,
+            mean_fitness,
+            worst_fitness,
+            std_fitness: variance.sqrt(),
+            mean_selected_features,
+            diversity: DiversityMetrics::of(population),
+            elapsed_secs: elapsed.as_secs_f64(),
+            evaluations,
+            duplicates_removed: 0,
+        }
+    }
+}
+
+// What the run should do after an observer has seen a generation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObserverAction {
+    Continue,
+    Stop,
+}
+
+// Hooks into a GA run. Every method has an empty default, so an observer only implements what
+// it needs. Folds of a cross-validation run in parallel, so observers are shared across threads
+// and keep any state behind a lock or atomic.
+pub trait GaObserver: Send + Sync {
+    // Called after every generation is evaluated; returning `ObserverAction::Stop` ends the run.
+    fn on_generation_end(&self, _stats: &GenerationStats) -> ObserverAction {
+        ObserverAction::Continue
+    }
+
+    // Called when a run continues from a checkpoint written after `generations_run` generations.
+    fn on_resume(&self, _fold: usize, _generations_run: usize) {}
+
+    // Called whenever the run finds an individual fitter than every one before it.
+    fn on_new_best(&self, _fold: usize, _generation: usize, _best: &Individual) {}
+
+    // Called when a cross-validation fold is done, with the fitness of its best individual on the
+    // validation split.
+    fn on_fold_end(&self, _fold: usize, _validation_fitness: f32, _best: &Individual) {}
+
+    // Called once per GA run, i.e. once per fold in cross-validation.
+    fn on_run_end(&self, _fold: usize, _result: &GaResult) {}
+}
+
+// Prints one line per generation and per fold, and a summary at the end of every run.
+pub struct ConsoleObserver;
+
+impl GaObserver for ConsoleObserver {
+    fn on_generation_end(&self, stats: &GenerationStats) -> ObserverAction {
+        println!(
+            "fold {} generation {}: best {:.6} mean {:.6} features {:.1} diversity {:.3} unique {:.2}",
+            stats.fold,
+            stats.generation,
+            stats.best_fitness,
+            stats.mean_fitness,
+            stats.mean_selected_features,
+            stats.diversity.mean_hamming_distance,
+            stats.diversity.unique_fraction
+        );
+        ObserverAction::Continue
+    }
+
+    fn on_resume(&self, fold: usize, generations_run: usize) {
+        println!(
+            "fold {} resuming after generation {}",
+            fold, generations_run
+        );
+    }
+
+    fn on_fold_end(&self, fold: usize, validation_fitness: f32, best: &Individual) {
+        println!(
+            "fold {} done: validation fitness {} with {} features",
+            fold,
+            validation_fitness,
+            best.features().count_ones()
+        );
+    }
+
+    fn on_run_end(&self, fold: usize, result: &GaResult) {
+        println!(
+            "fold {} Best Fitness: {} (MSE: {})",
+            fold,
+            result.best.fitness(),
+            result.best.error()
+        );
+        println!("Selected Features: {:?}", result.best.features());
+        println!(
+            "Stopped after {} generations: {}",
+            result.generations_run, result.stop_reason
+        );
+        let stats = result.cache_stats;
+        println!(
+            "Fitness cache: {} hits / {} lookups ({:.1}% hit rate), {} distinct masks trained",
+            stats.hits,
+            stats.lookups(),
+            stats.hit_rate() * 100.0,
+            stats.entries
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chromosome::Chromosome, config::Parsimony};
+
+    fn scored(mask: Vec<bool>, negative_mse: f32) -> Individual {
+        let mut individual = Individual::from_features(Chromosome::from(mask));
+        individual.set_score(negative_mse, Parsimony::default());
+        individual
+    }
+
+    #[test]
+    fn unscored_individuals_are_left_out() {
+        let population = vec![
+            scored(vec![true, false], -1.0),
+            scored(vec![true, true], -3.0),
+            scored(vec![false, false], -2.0),
+        ];
+        let stats = GenerationStats::from_population(0, 1, &population, Duration::ZERO, 3);
+        assert_eq!(stats.best_fitness, -1.0);
+        assert_eq!(stats.mean_fitness, -2.0);
+        assert_eq!(stats.worst_fitness, -3.0);
+        assert_eq!(stats.std_fitness, 1.0);
+        assert_eq!(stats.mean_selected_features, 1.0);
+    }
+
+    #[test]
+    fn population_without_a_score_reports_nan() {
+        let population = vec![scored(vec![false, false], -1.0); 3];
+        let stats = GenerationStats::from_population(0, 1, &population, Duration::ZERO, 3);
+        assert!(stats.best_fitness.is_nan());
+        assert!(stats.mean_fitness.is_nan());
+        assert!(stats.worst_fitness.is_nan());
+        assert!(stats.std_fitness.is_nan());
+    }
+}
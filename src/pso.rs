@@ -0,0 +1,151 @@
+use std::{iter::Sum, sync::Arc, time::Instant};
+
+use rand::Rng;
+
+use crate::{
+    cache::FitnessCache,
+    chromosome::Chromosome,
+    config::PsoConfig,
+    data::DataSet,
+    ga::{
+        evaluate_masks, finish_selector, new_rng, GaResult, GaRng, HallOfFame, Individual,
+        StopReason,
+    },
+    model::ModelName,
+    observer::GenerationStats,
+};
+
+struct Particle {
+    position: Chromosome,
+    velocity: Vec<f64>,
+    best: Individual,
+}
+
+impl Particle {
+    // Standard binary PSO update: every gene is set with probability sigmoid(velocity).
+    fn step(&mut self, global_best: &Chromosome, config: &PsoConfig, rng: &mut GaRng) {
+        for gene in 0..self.position.len() {
+            let x = bit(self.position.get(gene));
+            let velocity = config.inertia * self.velocity[gene]
+                + config.cognitive * rng.gen::<f64>() * (bit(self.best.features().get(gene)) - x)
+                + config.social * rng.gen::<f64>() * (bit(global_best.get(gene)) - x);
+            let velocity = velocity.clamp(-config.max_velocity, config.max_velocity);
+            self.velocity[gene] = velocity;
+            self.position
+                .set(gene, rng.gen::<f64>() < sigmoid(velocity));
+        }
+        let mut repaired = Individual::from_features(self.position.clone());
+        repaired.repair(&config.search.constraints, rng);
+        self.position = repaired.features().clone();
+    }
+}
+
+fn bit(value: bool) -> f64 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+// Binary particle swarm optimisation over feature masks. Every particle keeps a real velocity
+// per gene, pulled towards its own best mask and the best mask of the swarm, and samples its
+// next mask through a sigmoid of that velocity. The swarm is evaluated in parallel through the
+// fitness cache until `config.search.evaluations` is spent; each iteration is one history entry.
+pub fn run_pso<D>(dataset: Arc<D>, model: ModelName, config: PsoConfig) -> Result<GaResult, String>
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    let num_features = dataset.dimension().1;
+    config.search.constraints.validate(num_features)?;
+    let num_particles = config.particles.max(1);
+    let iterations = (config.search.evaluations / num_particles).max(1);
+    let mut rng = new_rng(config.search.seed);
+    let cache = FitnessCache::new();
+    let mut hall_of_fame = HallOfFame::new(config.search.hall_of_fame_size.max(1));
+    let mut history = vec![];
+    let started = Instant::now();
+
+    let mut positions: Vec<Chromosome> = (0..num_particles)
+        .map(|_| {
+            let mut individual = Individual::new(num_features, &mut rng);
+            individual.repair(&config.search.constraints, &mut rng);
+            individual.features().clone()
+        })
+        .collect();
+    let mut swarm: Vec<Particle> = vec![];
+    let mut global_best: Option<Individual> = None;
+
+    for iteration in 0..iterations {
+        let evaluated = evaluate_masks(
+            positions,
+            dataset.as_ref(),
+            &model,
+            &cache,
+            config.search.parsimony,
+        );
+        hall_of_fame.update(&evaluated);
+        history.push(GenerationStats::from_population(
+            0,
+            iteration + 1,
+            &evaluated,
+            started.elapsed(),
+            (iteration + 1) * num_particles,
+        ));
+
+        if swarm.is_empty() {
+            swarm = evaluated
+                .iter()
+                .map(|individual| Particle {
+                    position: individual.features().clone(),
+                    velocity: (0..num_features)
+                        .map(|_| rng.gen_range(-config.max_velocity..=config.max_velocity))
+                        .collect(),
+                    best: individual.clone(),
+                })
+                .collect();
+        } else {
+            for (particle, individual) in swarm.iter_mut().zip(&evaluated) {
+                if individual.fitness() > particle.best.fitness() {
+                    particle.best = individual.clone();
+                }
+            }
+        }
+        for particle in &swarm {
+            if global_best
+                .as_ref()
+                .is_none_or(|best| particle.best.fitness() > best.fitness())
+            {
+                global_best = Some(particle.best.clone());
+            }
+        }
+
+        if iteration + 1 == iterations {
+            break;
+        }
+        let leader = global_best.as_ref().map_or_else(
+            || Chromosome::new(num_features),
+            |best| best.features().clone(),
+        );
+        for particle in &mut swarm {
+            particle.step(&leader, &config, &mut rng);
+        }
+        positions = swarm
+            .iter()
+            .map(|particle| particle.position.clone())
+            .collect();
+    }
+
+    Ok(finish_selector(
+        hall_of_fame,
+        history,
+        StopReason::BudgetExhausted,
+        &cache,
+        num_features,
+    ))
+}
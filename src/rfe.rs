@@ -0,0 +1,85 @@
+use std::{iter::Sum, sync::Arc};
+
+use crate::{
+    chromosome::Chromosome,
+    config::RfeConfig,
+    data::DataSet,
+    ga::{cross_validated_fitness, Individual},
+    importance::feature_importances,
+    model::ModelName,
+};
+
+// One point of the recursive feature elimination curve.
+#[derive(Clone, Debug)]
+pub struct RfeStep {
+    pub features: Chromosome,
+    // mean cross-validated fitness (negative MSE)
+    pub cv_fitness: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct RfeResult {
+    // from every feature down to `min_features`
+    pub curve: Vec<RfeStep>,
+    // subset at the best point of the curve; ties go to the smaller subset
+    pub best: Chromosome,
+    pub best_fitness: f32,
+}
+
+// Recursive feature elimination: fit `model` on the remaining features, rank them by importance
+// (see `importance::feature_importances`), drop the `step` weakest and repeat. Every subset on
+// the way is scored by k-fold cross-validation.
+pub fn run_rfe<D>(dataset: Arc<D>, model: ModelName, config: RfeConfig) -> RfeResult
+where
+    D: DataSet + Sync + Send,
+    D::Input: Send + Sync,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    let num_features = dataset.dimension().1;
+    let min_features = config.min_features.max(1);
+    let mut current = Chromosome::new_all_true(num_features);
+    let mut curve: Vec<RfeStep> = vec![];
+    // fewer than two folds leaves no training or no validation rows
+    let k_folds = config.k_folds.max(2);
+
+    loop {
+        let cv_fitness = cross_validated_fitness(
+            &Individual::from_features(current.clone()),
+            dataset.as_ref(),
+            model.clone(),
+            k_folds,
+        );
+        curve.push(RfeStep {
+            features: current.clone(),
+            cv_fitness,
+        });
+
+        let remaining = current.count_ones();
+        if remaining <= min_features {
+            break;
+        }
+        let importances = feature_importances(dataset.as_ref(), &current, &model, config.seed);
+        let mut ranked: Vec<(usize, f32)> = current.iter_ones().zip(importances).collect();
+        ranked.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let removed = config.step.max(1).min(remaining - min_features);
+        for &(gene, _) in &ranked[..removed] {
+            current.set(gene, false);
+        }
+    }
+
+    let best_step = curve
+        .iter()
+        .fold(&curve[0], |best, step| {
+            if step.cv_fitness >= best.cv_fitness {
+                step
+            } else {
+                best
+            }
+        })
+        .clone();
+    RfeResult {
+        best: best_step.features,
+        best_fitness: best_step.cv_fitness,
+        curve,
+    }
+}
@@ -0,0 +1,229 @@
+use std::{iter::Sum, sync::Arc, time::Instant};
+
+use crate::{
+    cache::FitnessCache,
+    chromosome::Chromosome,
+    config::{SequentialConfig, SequentialDirection},
+    data::DataSet,
+    ga::{
+        evaluate_masks, finish_selector, sort_by_fitness, GaResult, HallOfFame, Individual,
+        StopReason,
+    },
+    model::ModelName,
+    observer::GenerationStats,
+};
+
+// State shared by the steps of one sequential search.
+struct Search<'a, D> {
+    dataset: &'a D,
+    model: ModelName,
+    config: &'a SequentialConfig,
+    cache: FitnessCache,
+    // best subset found so far for every subset size
+    best_by_size: Vec<Option<Individual>>,
+    hall_of_fame: HallOfFame,
+    history: Vec<GenerationStats>,
+    evaluations: usize,
+    started: Instant,
+}
+
+impl<D> Search<'_, D>
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    // Evaluate every mask reachable from `current` by flipping one of `genes`, record the step
+    // and return the best of them.
+    fn step(&mut self, current: &Chromosome, genes: &[usize]) -> Option<Individual> {
+        let masks: Vec<Chromosome> = genes
+            .iter()
+            .map(|&gene| {
+                let mut mask = current.clone();
+                mask.flip(gene);
+                mask
+            })
+            .collect();
+        self.evaluate(masks)
+    }
+
+    // Evaluate `masks` as one step of the history and return the best of them.
+    fn evaluate(&mut self, masks: Vec<Chromosome>) -> Option<Individual> {
+        if masks.is_empty() {
+            return None;
+        }
+        let mut candidates = evaluate_masks(
+            masks,
+            self.dataset,
+            &self.model,
+            &self.cache,
+            self.config.parsimony,
+        );
+        sort_by_fitness(&mut candidates);
+        self.evaluations += candidates.len();
+        // the search walks through sizes outside the bounds, but only feasible subsets count
+        let feasible: Vec<Individual> = candidates
+            .iter()
+            .filter(|candidate| self.config.constraints.is_feasible(candidate.features()))
+            .cloned()
+            .collect();
+        self.hall_of_fame.update(&feasible);
+        self.history.push(GenerationStats::from_population(
+            0,
+            self.history.len() + 1,
+            &candidates,
+            self.started.elapsed(),
+            self.evaluations,
+        ));
+        candidates.into_iter().next()
+    }
+
+    // Keep `individual` if it beats the best subset of its size; returns whether it did.
+    fn record(&mut self, individual: &Individual) -> bool {
+        let size = individual.features().count_ones();
+        let improves = self.best_by_size[size]
+            .as_ref()
+            .is_none_or(|best| individual.fitness() > best.fitness());
+        if improves {
+            self.best_by_size[size] = Some(individual.clone());
+        }
+        improves
+    }
+
+    // Genes that may be switched on in `current`.
+    fn addable(&self, current: &Chromosome) -> Vec<usize> {
+        (0..current.len())
+            .filter(|&gene| !current.get(gene) && !self.config.constraints.excluded.contains(&gene))
+            .collect()
+    }
+
+    // Genes that may be switched off in `current`, except `keep`.
+    fn removable(&self, current: &Chromosome, keep: Option<usize>) -> Vec<usize> {
+        current
+            .iter_ones()
+            .filter(|&gene| Some(gene) != keep && !self.config.constraints.required.contains(&gene))
+            .collect()
+    }
+
+    // Conditional step of the floating variants: keep moving against the search direction while
+    // that gives the best subset seen for the resulting size. `last` is the gene the main step
+    // just changed and is not changed back.
+    fn float_back(
+        &mut self,
+        mut current: Individual,
+        last: usize,
+        min: usize,
+        max: usize,
+    ) -> Individual {
+        loop {
+            let size = current.features().count_ones();
+            let genes = match self.config.direction {
+                SequentialDirection::Forward if size > min.max(1) => {
+                    self.removable(current.features(), Some(last))
+                }
+                SequentialDirection::Backward if size < max => {
+                    let mut genes = self.addable(current.features());
+                    genes.retain(|&gene| gene != last);
+                    genes
+                }
+                _ => return current,
+            };
+            match self.step(current.features(), &genes) {
+                Some(candidate) if self.record(&candidate) => current = candidate,
+                _ => return current,
+            }
+        }
+    }
+}
+
+// Sequential forward/backward selection and their floating variants, chosen by `config`. Every
+// step evaluates its candidate subsets in parallel and becomes one entry of the history, so the
+// result reads like a GA run. `best` is the fittest subset over all sizes visited within the
+// size bounds of `config.constraints`.
+pub fn run_sequential<D>(
+    dataset: Arc<D>,
+    model: ModelName,
+    config: SequentialConfig,
+) -> Result<GaResult, String>
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    let num_features = dataset.dimension().1;
+    config.constraints.validate(num_features)?;
+    let min = config
+        .constraints
+        .min_features
+        .max(config.constraints.required.len());
+    let max = config
+        .constraints
+        .max_features
+        .unwrap_or(num_features)
+        .min(num_features - config.constraints.excluded.len());
+
+    let mut search = Search {
+        dataset: dataset.as_ref(),
+        model,
+        config: &config,
+        cache: FitnessCache::new(),
+        best_by_size: vec![None; num_features + 1],
+        hall_of_fame: HallOfFame::new(config.hall_of_fame_size.max(1)),
+        history: vec![],
+        evaluations: 0,
+        started: Instant::now(),
+    };
+
+    let mut start = match config.direction {
+        SequentialDirection::Forward => Chromosome::new(num_features),
+        SequentialDirection::Backward => Chromosome::new_all_true(num_features),
+    };
+    for &gene in &config.constraints.required {
+        start.set(gene, true);
+    }
+    for &gene in &config.constraints.excluded {
+        start.set(gene, false);
+    }
+    // A non-empty starting subset is a candidate of its own.
+    let mut current = Individual::from_features(start.clone());
+    if start.count_ones() > 0 {
+        if let Some(evaluated) = search.evaluate(vec![start]) {
+            search.record(&evaluated);
+            current = evaluated;
+        }
+    }
+
+    loop {
+        let size = current.features().count_ones();
+        let (genes, done) = match config.direction {
+            SequentialDirection::Forward => (search.addable(current.features()), size >= max),
+            SequentialDirection::Backward => (
+                search.removable(current.features(), None),
+                size <= min.max(1),
+            ),
+        };
+        if done {
+            break;
+        }
+        let Some(next) = search.step(current.features(), &genes) else {
+            break;
+        };
+        let changed = next
+            .features()
+            .iter_ones()
+            .chain(current.features().iter_ones())
+            .find(|&gene| next.features().get(gene) != current.features().get(gene))
+            .unwrap_or(0);
+        search.record(&next);
+        current = next;
+        if config.floating {
+            current = search.float_back(current, changed, min, max);
+        }
+    }
+
+    Ok(finish_selector(
+        search.hall_of_fame,
+        search.history,
+        StopReason::Completed,
+        &search.cache,
+        num_features,
+    ))
+}
@@ -0,0 +1,210 @@
+use std::{iter::Sum, sync::Arc};
+
+use rand::Rng;
+
+use crate::{
+    chromosome::Chromosome,
+    config::{GaConfig, StabilityConfig},
+    data::DataSet,
+    ga::{new_rng, run_ga},
+    model::ModelName,
+};
+
+// How consistently a selector picks the same features over several runs.
+#[derive(Clone, Debug)]
+pub struct StabilityReport {
+    // share of runs that selected the feature, in column order
+    pub frequencies: Vec<(String, f64)>,
+    // best mask of every run
+    pub masks: Vec<Chromosome>,
+    // Nogueira et al. (2018) stability, 1.0 for identical masks and about 0.0 for random ones
+    pub nogueira: f64,
+    // mean pairwise Kuncheva (2007) index; only defined when every mask has the same size
+    pub kuncheva: Option<f64>,
+}
+
+impl StabilityReport {
+    // Frequencies and stability indices of masks from any selector.
+    pub fn from_masks(feature_names: Vec<String>, masks: Vec<Chromosome>) -> Self {
+        let runs = masks.len().max(1) as f64;
+        let frequencies = feature_names
+            .into_iter()
+            .enumerate()
+            .map(|(feature, name)| {
+                let selected = masks.iter().filter(|mask| mask.get(feature)).count();
+                (name, selected as f64 / runs)
+            })
+            .collect();
+        Self {
+            nogueira: nogueira_index(&masks),
+            kuncheva: kuncheva_index(&masks),
+            frequencies,
+            masks,
+        }
+    }
+
+    // Features selected in at least `threshold` (in [0, 1]) of the runs.
+    pub fn consensus(&self, threshold: f64) -> Chromosome {
+        Chromosome::from(
+            self.frequencies
+                .iter()
+                .map(|(_, frequency)| *frequency >= threshold)
+                .collect::<Vec<bool>>(),
+        )
+    }
+}
+
+// 1 - mean unbiased per-feature selection variance over its expected value for random masks
+// of the same mean size. NaN with fewer than two masks or when every mask is empty or full.
+pub fn nogueira_index(masks: &[Chromosome]) -> f64 {
+    let runs = masks.len();
+    let Some(num_features) = masks.first().map(Chromosome::len) else {
+        return f64::NAN;
+    };
+    if runs < 2 || num_features == 0 {
+        return f64::NAN;
+    }
+    let m = runs as f64;
+    let n = num_features as f64;
+    let mean_size = masks.iter().map(|mask| mask.count_ones()).sum::<usize>() as f64 / m;
+    let mean_variance = (0..num_features)
+        .map(|feature| {
+            let p = masks.iter().filter(|mask| mask.get(feature)).count() as f64 / m;
+            m / (m - 1.0) * p * (1.0 - p)
+        })
+        .sum::<f64>()
+        / n;
+    let expected = mean_size / n * (1.0 - mean_size / n);
+    if expected == 0.0 {
+        return f64::NAN;
+    }
+    1.0 - mean_variance / expected
+}
+
+// Mean over all pairs of (r n - k^2) / (k (n - k)), with r the shared features and k the subset
+// size. `None` when sizes differ, a subset is empty or full, or there are fewer than two masks.
+pub fn kuncheva_index(masks: &[Chromosome]) -> Option<f64> {
+    let first = masks.first()?;
+    let n = first.len() as f64;
+    let k = first.count_ones();
+    if masks.len() < 2 || k == 0 || k == first.len() {
+        return None;
+    }
+    if masks.iter().any(|mask| mask.count_ones() != k) {
+        return None;
+    }
+    let k = k as f64;
+    let mut total = 0.0;
+    let mut pairs = 0;
+    for (i, a) in masks.iter().enumerate() {
+        for b in &masks[i + 1..] {
+            let shared = a.iter_ones().filter(|&feature| b.get(feature)).count() as f64;
+            total += (shared * n - k * k) / (k * (n - k));
+            pairs += 1;
+        }
+    }
+    Some(total / pairs as f64)
+}
+
+// Run the GA on the training part of every fold, on bootstrap resamples and repeatedly on the
+// whole dataset, and report how often each feature ends up in the best mask. Every run gets
+// its own seed derived from `gaconfig.seed`; checkpoints are disabled.
+pub fn run_stability_selection<D>(
+    dataset: Arc<D>,
+    model: ModelName,
+    gaconfig: GaConfig,
+    config: StabilityConfig,
+) -> Result<StabilityReport, String>
+where
+    D: DataSet + Sync + Send,
+    f32: Sum<<D as DataSet>::Input>,
+{
+    let num_samples = dataset.dimension().0;
+    let mut rng = new_rng(gaconfig.seed);
+    let mut run = 0u64;
+    let mut best_mask = |data: Arc<D>| {
+        let runconfig = GaConfig {
+            seed: gaconfig.seed.map(|seed| seed.wrapping_add(run)),
+            checkpoint_path: None,
+            ..gaconfig.clone()
+        };
+        run += 1;
+        run_ga(data, model.clone(), runconfig).map(|result| result.best.features().clone())
+    };
+
+    let mut masks = vec![];
+    // a single fold has no training rows
+    for fold in (0..config.k_folds).filter(|_| config.k_folds > 1) {
+        let (train, _) = dataset.split_for_cross_validation(config.k_folds, fold);
+        masks.push(best_mask(Arc::new(train))?);
+    }
+    for _ in 0..config.bootstraps {
+        let rows: Vec<usize> = (0..num_samples)
+            .map(|_| rng.gen_range(0..num_samples))
+            .collect();
+        masks.push(best_mask(Arc::new(dataset.resample(&rows)))?);
+    }
+    for _ in 0..config.repeats {
+        masks.push(best_mask(dataset.clone())?);
+    }
+
+    Ok(StabilityReport::from_masks(dataset.features(), masks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn masks(rows: &[&str]) -> Vec<Chromosome> {
+        rows.iter()
+            .map(|row| Chromosome::from(row.chars().map(|c| c == '1').collect::<Vec<bool>>()))
+            .collect()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn identical_masks_are_perfectly_stable() {
+        let same = masks(&["101100", "101100", "101100"]);
+        assert!(close(nogueira_index(&same), 1.0));
+        assert!(close(kuncheva_index(&same).unwrap(), 1.0));
+    }
+
+    #[test]
+    fn hand_computed_pairs() {
+        // one shared feature out of two is what chance gives for k = 2, n = 4
+        let half = masks(&["1100", "1010"]);
+        assert!(close(nogueira_index(&half), 0.0));
+        assert!(close(kuncheva_index(&half).unwrap(), 0.0));
+        // disjoint halves
+        let disjoint = masks(&["1100", "0011"]);
+        assert!(close(nogueira_index(&disjoint), -1.0));
+        assert!(close(kuncheva_index(&disjoint).unwrap(), -1.0));
+        // every pair shares two of three features: (2 * 6 - 9) / (3 * 3) = 1/3
+        let three = masks(&["111000", "110100", "011100"]);
+        assert!(close(nogueira_index(&three), 1.0 / 3.0));
+        assert!(close(kuncheva_index(&three).unwrap(), 1.0 / 3.0));
+    }
+
+    #[test]
+    fn undefined_cases() {
+        assert!(nogueira_index(&masks(&["1100"])).is_nan());
+        assert!(nogueira_index(&masks(&["0000", "0000"])).is_nan());
+        assert_eq!(kuncheva_index(&masks(&["1100", "1000"])), None);
+        assert_eq!(kuncheva_index(&masks(&["1111", "1111"])), None);
+    }
+
+    #[test]
+    fn frequencies_and_consensus() {
+        let names = (0..4).map(|i| format!("f{}", i)).collect();
+        let report = StabilityReport::from_masks(names, masks(&["1100", "1010", "1000", "0001"]));
+        let frequencies: Vec<f64> = report.frequencies.iter().map(|(_, f)| *f).collect();
+        assert_eq!(frequencies, vec![0.75, 0.25, 0.25, 0.25]);
+        assert_eq!(
+            report.consensus(0.5),
+            Chromosome::from(vec![true, false, false, false])
+        );
+    }
+}